@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::env;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::{BoundingBox, Grid, MapRenderer, Pos};
+
+//  Receives simulation states one by one, `focus` is an optional point of interest
+pub trait FrameSink<T> {
+    fn push(&mut self, map: &HashMap<Pos, T>, focus: Option<Pos>);
+
+    //  Same for a grid where None is an empty cell, sinks that discard frames skip the conversion
+    fn push_grid(&mut self, grid: &Grid<Option<T>>, focus: Option<Pos>) where T: Clone {
+        self.push(&grid.to_filled_map(), focus);
+    }
+}
+
+//  Discards all frames, for running simulations without output
+pub struct NoFrames;
+
+impl<T> FrameSink<T> for NoFrames {
+    fn push(&mut self, _: &HashMap<Pos, T>, _: Option<Pos>) {}
+
+    fn push_grid(&mut self, _: &Grid<Option<T>>, _: Option<Pos>) where T: Clone {}
+}
+
+//  None discards all frames, for sinks that are chosen at runtime
+impl<T, S> FrameSink<T> for Option<S> where S: FrameSink<T> {
+    fn push(&mut self, map: &HashMap<Pos, T>, focus: Option<Pos>) {
+        if let Some(sink) = self {
+            sink.push(map, focus);
+        }
+    }
+
+    fn push_grid(&mut self, grid: &Grid<Option<T>>, focus: Option<Pos>) where T: Clone {
+        if let Some(sink) = self {
+            sink.push_grid(grid, focus);
+        }
+    }
+}
+
+//  Redraws every frame in place using ANSI escape codes. Stops drawing after the first
+//  write error, like a closed pipe, the simulation itself goes on
+pub struct TerminalPlayer<W> where W: Write {
+    out: W,
+    renderer: MapRenderer,
+    frame_time: Option<Duration>,
+    every: usize,
+    follow: Option<(i32, i32)>,
+    frames: usize,
+    last_frame: Option<Instant>,
+    failed: bool,
+}
+
+impl TerminalPlayer<io::Stdout> {
+    pub fn stdout() -> TerminalPlayer<io::Stdout> {
+        TerminalPlayer::new(io::stdout())
+    }
+
+    //  Player on stdout when the `ANIMATE` environment variable is set,
+    //  its value is the frame rate and defaults to 30
+    pub fn from_env() -> Option<TerminalPlayer<io::Stdout>> {
+        let fps = env::var("ANIMATE").ok()?;
+        Some(TerminalPlayer::stdout().fps(fps.parse().unwrap_or(30)))
+    }
+}
+
+impl<W> TerminalPlayer<W> where W: Write {
+    pub fn new(out: W) -> TerminalPlayer<W> {
+        TerminalPlayer {
+            out,
+            renderer: MapRenderer::new(),
+            frame_time: None,
+            every: 1,
+            follow: None,
+            frames: 0,
+            last_frame: None,
+            failed: false,
+        }
+    }
+
+    pub fn renderer(mut self, renderer: MapRenderer) -> TerminalPlayer<W> {
+        self.renderer = renderer;
+        self
+    }
+
+    pub fn fps(mut self, fps: u32) -> TerminalPlayer<W> {
+        assert!(fps > 0);
+        self.frame_time = Some(Duration::from_secs(1) / fps);
+        self
+    }
+
+    //  Only draws every n-th frame
+    pub fn every(mut self, n: usize) -> TerminalPlayer<W> {
+        assert!(n > 0);
+        self.every = n;
+        self
+    }
+
+    //  Keeps a window of `rows` x `cols` centered on the focus of each frame
+    pub fn follow(mut self, rows: i32, cols: i32) -> TerminalPlayer<W> {
+        assert!(rows > 0 && cols > 0);
+        self.follow = Some((rows, cols));
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn wait_for_next_frame(&mut self) {
+        if let (Some(frame_time), Some(last_frame)) = (self.frame_time, self.last_frame) {
+            if let Some(remaining) = frame_time.checked_sub(last_frame.elapsed()) {
+                thread::sleep(remaining);
+            }
+        }
+        self.last_frame = Some(Instant::now());
+    }
+
+    fn draw(&mut self, frame: &str) -> io::Result<()> {
+        //  Clear the screen once, then move the cursor home and overwrite the previous frame
+        if self.frames == 1 {
+            write!(self.out, "\x1b[2J")?;
+        }
+        write!(self.out, "\x1b[H{}\x1b[J", frame.replace('\n', "\x1b[K\n"))?;
+        self.out.flush()
+    }
+}
+
+impl<T, W> FrameSink<T> for TerminalPlayer<W> where T: Display, W: Write {
+    fn push(&mut self, map: &HashMap<Pos, T>, focus: Option<Pos>) {
+        self.frames += 1;
+        if self.failed || !(self.frames - 1).is_multiple_of(self.every) {
+            return;
+        }
+
+        let frame = match (self.follow, focus) {
+            (Some((rows, cols)), Some(focus)) => {
+                let min_row = focus.row - rows / 2;
+                let min_col = focus.col - cols / 2;
+                self.renderer.clone()
+                    .viewport(BoundingBox { min_row, max_row: min_row + rows - 1, min_col, max_col: min_col + cols - 1 })
+                    .render_to_string(map)
+            }
+            _ => self.renderer.render_to_string(map)
+        };
+
+        self.wait_for_next_frame();
+        self.failed = self.draw(&frame).is_err();
+    }
+}
+
+#[test]
+fn test_terminal_player() {
+    let mut player = TerminalPlayer::new(Vec::new())
+        .every(2)
+        .follow(1, 3);
+
+    let mut map = HashMap::new();
+    for col in 0..3 {
+        map.insert(Pos { row: 0, col }, '#');
+        player.push(&map, Some(Pos { row: 0, col }));
+    }
+
+    let out = String::from_utf8(player.into_inner()).unwrap();
+    assert_eq!("\x1b[2J\x1b[H.#.\x1b[K\n\x1b[J\x1b[H##.\x1b[K\n\x1b[J", out);
+
+    let mut player = TerminalPlayer::new(Vec::new());
+    let grid = Grid::from_rows(vec![vec![Some('#'), None, Some('#')]]);
+    player.push_grid(&grid, None);
+    assert_eq!("\x1b[2J\x1b[H#.#\x1b[K\n\x1b[J", String::from_utf8(player.into_inner()).unwrap());
+
+    //  A full buffer fails on the first frame and is never written to again
+    let mut buf = [0u8; 4];
+    let mut player = TerminalPlayer::new(&mut buf[..]);
+    let mut none: Option<TerminalPlayer<Vec<u8>>> = None;
+    for _ in 0..3 {
+        player.push(&map, None);
+        none.push(&map, None);
+    }
+    assert!(player.failed);
+    assert_eq!(b"\x1b[2J", &buf);
+}
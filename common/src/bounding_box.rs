@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+use crate::Pos;
+
+//  Inclusive rectangle of positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundingBox {
+    pub min_row: i32,
+    pub max_row: i32,
+    pub min_col: i32,
+    pub max_col: i32,
+}
+
+impl BoundingBox {
+    pub fn at(pos: &Pos) -> BoundingBox {
+        BoundingBox { min_row: pos.row, max_row: pos.row, min_col: pos.col, max_col: pos.col }
+    }
+
+    pub fn from_positions<I>(positions: I) -> Option<BoundingBox> where I: IntoIterator<Item=Pos> {
+        let mut positions = positions.into_iter();
+        let mut bounds = BoundingBox::at(&positions.next()?);
+        for pos in positions {
+            bounds.include(&pos);
+        }
+        Some(bounds)
+    }
+
+    //  None for an empty map
+    pub fn of_map<T>(map: &HashMap<Pos, T>) -> Option<BoundingBox> {
+        BoundingBox::from_positions(map.keys().copied())
+    }
+
+    pub fn include(&mut self, pos: &Pos) {
+        self.min_row = self.min_row.min(pos.row);
+        self.max_row = self.max_row.max(pos.row);
+        self.min_col = self.min_col.min(pos.col);
+        self.max_col = self.max_col.max(pos.col);
+    }
+
+    pub fn contains(&self, pos: &Pos) -> bool {
+        self.min_row <= pos.row && pos.row <= self.max_row
+            && self.min_col <= pos.col && pos.col <= self.max_col
+    }
+
+    pub fn width(&self) -> i32 {
+        self.max_col - self.min_col + 1
+    }
+
+    pub fn height(&self) -> i32 {
+        self.max_row - self.min_row + 1
+    }
+
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min_row: self.min_row.min(other.min_row),
+            max_row: self.max_row.max(other.max_row),
+            min_col: self.min_col.min(other.min_col),
+            max_col: self.max_col.max(other.max_col),
+        }
+    }
+
+    pub fn intersection(&self, other: &BoundingBox) -> Option<BoundingBox> {
+        let bounds = BoundingBox {
+            min_row: self.min_row.max(other.min_row),
+            max_row: self.max_row.min(other.max_row),
+            min_col: self.min_col.max(other.min_col),
+            max_col: self.max_col.min(other.max_col),
+        };
+        if bounds.min_row <= bounds.max_row && bounds.min_col <= bounds.max_col {
+            Some(bounds)
+        } else {
+            None
+        }
+    }
+
+    //  Grows every side by `n`, negative values shrink
+    pub fn expand(&self, n: i32) -> BoundingBox {
+        BoundingBox {
+            min_row: self.min_row - n,
+            max_row: self.max_row + n,
+            min_col: self.min_col - n,
+            max_col: self.max_col + n,
+        }
+    }
+
+    //  Row by row, left to right
+    pub fn positions(&self) -> impl Iterator<Item=Pos> {
+        let bounds = *self;
+        (bounds.min_row..=bounds.max_row)
+            .flat_map(move |row| (bounds.min_col..=bounds.max_col).map(move |col| Pos { row, col }))
+    }
+}
+
+//  HashMap that keeps its bounding box up to date as cells are inserted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundedMap<T> {
+    map: HashMap<Pos, T>,
+    bounds: Option<BoundingBox>,
+}
+
+impl<T> Default for BoundedMap<T> {
+    fn default() -> Self {
+        BoundedMap { map: HashMap::new(), bounds: None }
+    }
+}
+
+impl<T> BoundedMap<T> {
+    pub fn new() -> BoundedMap<T> {
+        BoundedMap::default()
+    }
+
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        self.bounds
+    }
+
+    pub fn insert(&mut self, pos: Pos, value: T) -> Option<T> {
+        match &mut self.bounds {
+            None => self.bounds = Some(BoundingBox::at(&pos)),
+            Some(bounds) => bounds.include(&pos),
+        }
+        self.map.insert(pos, value)
+    }
+
+    //  Bounds are only rescanned when a cell on the edge is removed
+    pub fn remove(&mut self, pos: &Pos) -> Option<T> {
+        let removed = self.map.remove(pos);
+        if let (Some(_), Some(bounds)) = (&removed, self.bounds) {
+            if pos.row == bounds.min_row || pos.row == bounds.max_row
+                || pos.col == bounds.min_col || pos.col == bounds.max_col {
+                self.bounds = BoundingBox::of_map(&self.map);
+            }
+        }
+        removed
+    }
+
+    pub fn into_inner(self) -> HashMap<Pos, T> {
+        self.map
+    }
+}
+
+impl<T> Deref for BoundedMap<T> {
+    type Target = HashMap<Pos, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<T> From<HashMap<Pos, T>> for BoundedMap<T> {
+    fn from(map: HashMap<Pos, T>) -> Self {
+        let bounds = BoundingBox::of_map(&map);
+        BoundedMap { map, bounds }
+    }
+}
+
+impl<T> FromIterator<(Pos, T)> for BoundedMap<T> {
+    fn from_iter<I: IntoIterator<Item=(Pos, T)>>(iter: I) -> Self {
+        let mut map = BoundedMap::new();
+        for (pos, value) in iter {
+            map.insert(pos, value);
+        }
+        map
+    }
+}
+
+#[test]
+fn test_bounding_box() {
+    assert_eq!(None, BoundingBox::of_map::<char>(&HashMap::new()));
+
+    let a = BoundingBox { min_row: 0, max_row: 2, min_col: 0, max_col: 3 };
+    let b = BoundingBox { min_row: 2, max_row: 5, min_col: 3, max_col: 4 };
+    assert_eq!(3, a.height());
+    assert_eq!(4, a.width());
+    assert_eq!(BoundingBox { min_row: 0, max_row: 5, min_col: 0, max_col: 4 }, a.union(&b));
+    assert_eq!(Some(BoundingBox::at(&Pos { row: 2, col: 3 })), a.intersection(&b));
+    assert_eq!(None, a.intersection(&b.expand(-1)));
+    assert_eq!(12, a.positions().filter(|pos| a.contains(pos)).count());
+    assert!(!a.expand(1).positions().all(|pos| a.contains(&pos)));
+}
+
+#[test]
+fn test_bounded_map() {
+    let mut map = BoundedMap::new();
+    assert_eq!(None, map.bounds());
+    map.insert(Pos { row: 1, col: 1 }, '#');
+    map.insert(Pos { row: -2, col: 4 }, '#');
+    map.insert(Pos { row: 0, col: 2 }, '#');
+    assert_eq!(Some(BoundingBox { min_row: -2, max_row: 1, min_col: 1, max_col: 4 }), map.bounds());
+    map.remove(&Pos { row: -2, col: 4 });
+    assert_eq!(Some(BoundingBox { min_row: 0, max_row: 1, min_col: 1, max_col: 2 }), map.bounds());
+    assert_eq!(BoundingBox::of_map(&map), map.bounds());
+    assert!(map.contains_key(&Pos { row: 0, col: 2 }));
+}
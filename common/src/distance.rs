@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::{dijkstra, Graph, NodeId, Weight};
+
+//  Shortest path lengths between every pair of a set of nodes, None when unreachable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMatrix<K = NodeId> where K: Clone + Eq + Hash {
+    nodes: Vec<K>,
+    index: HashMap<K, usize>,
+    dist: Vec<Option<u32>>,
+}
+
+impl<K> DistanceMatrix<K> where K: Clone + Eq + Hash {
+    fn empty(nodes: Vec<K>) -> DistanceMatrix<K> {
+        let index = nodes.iter().enumerate().map(|(i, node)| (node.clone(), i)).collect();
+        let dist = vec![None; nodes.len() * nodes.len()];
+        DistanceMatrix { nodes, index, dist }
+    }
+
+    //  Floyd–Warshall over all nodes of the graph, nodes keep the graph's index order.
+    //  Like `dijkstra` it leaves out paths longer than u32::MAX
+    pub fn all_pairs<NA, EA>(graph: &Graph<NA, EA, K>) -> DistanceMatrix<K> where NA: Clone, EA: Weight {
+        let mut matrix = DistanceMatrix::empty(graph.nodes().cloned().collect());
+        let n = matrix.len();
+
+        for (i, u) in graph.node_indices().enumerate() {
+            matrix.dist[i * n + i] = Some(0);
+            for (v, weight) in graph.weighted_successors_at(u) {
+                let j = matrix.index[graph.key(v)];
+                if i != j && matrix.dist[i * n + j].is_none_or(|d| weight < d) {
+                    matrix.dist[i * n + j] = Some(weight);
+                }
+            }
+        }
+
+        for k in 0..n {
+            for i in 0..n {
+                let Some(ik) = matrix.dist[i * n + k] else { continue };
+                for j in 0..n {
+                    if let Some(ikj) = matrix.dist[k * n + j].and_then(|kj| ik.checked_add(kj)) {
+                        if matrix.dist[i * n + j].is_none_or(|d| ikj < d) {
+                            matrix.dist[i * n + j] = Some(ikj);
+                        }
+                    }
+                }
+            }
+        }
+
+        matrix
+    }
+
+    //  Distances between the given nodes only, paths may still go through any other node.
+    //  Runs one Dijkstra per node, which is cheaper than all pairs for a small subset
+    pub fn between<NA, EA>(graph: &Graph<NA, EA, K>, nodes: &[K]) -> DistanceMatrix<K> where NA: Clone, EA: Weight {
+        let mut unique = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            if !unique.contains(node) {
+                unique.push(node.clone());
+            }
+        }
+        let mut matrix = DistanceMatrix::empty(unique);
+        let n = matrix.len();
+
+        for i in 0..n {
+            let (dist, _) = dijkstra(graph, &matrix.nodes[i]);
+            for j in 0..n {
+                matrix.dist[i * n + j] = dist.get(&matrix.nodes[j]).copied();
+            }
+        }
+
+        matrix
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn nodes(&self) -> &[K] {
+        &self.nodes
+    }
+
+    pub fn index_of(&self, node: &K) -> Option<usize> {
+        self.index.get(node).copied()
+    }
+
+    pub fn node(&self, index: usize) -> &K {
+        &self.nodes[index]
+    }
+
+    pub fn distance_at(&self, from: usize, to: usize) -> Option<u32> {
+        self.dist[from * self.len() + to]
+    }
+
+    //  None also when either node isn't part of the matrix
+    pub fn distance(&self, from: &K, to: &K) -> Option<u32> {
+        self.distance_at(self.index_of(from)?, self.index_of(to)?)
+    }
+}
+
+#[test]
+fn test_distance_matrix() {
+    let [a, b, c, d, e] = ["a", "b", "c", "d", "e"].map(NodeId::from);
+    let mut graph: Graph<()> = Graph::from_edges([
+        (a.clone(), b.clone()), (b.clone(), c.clone()), (c.clone(), d.clone()), (d.clone(), a.clone()),
+    ]);
+    graph.insert_weighted_edge(&a, &c, 5);
+    graph.insert_node(&e, None);
+
+    let all = DistanceMatrix::all_pairs(&graph);
+    assert_eq!(5, all.len());
+    assert_eq!(Some(2), all.distance(&a, &c));
+    assert_eq!(Some(1), all.distance(&d, &a));
+    assert_eq!(Some(3), all.distance(&b, &a));
+    assert_eq!(Some(0), all.distance(&e, &e));
+    assert_eq!(None, all.distance(&a, &e));
+    assert_eq!(Some(2), all.index_of(&c));
+    assert_eq!(&c, all.node(2));
+
+    let subset = DistanceMatrix::between(&graph, &[d.clone(), b.clone(), d.clone()]);
+    assert_eq!(&[d.clone(), b.clone()], subset.nodes());
+    assert_eq!(Some(2), subset.distance(&d, &b));
+    assert_eq!(Some(2), subset.distance_at(1, 0));
+    assert_eq!(None, subset.distance(&a, &b));
+    for from in subset.nodes() {
+        for to in subset.nodes() {
+            assert_eq!(all.distance(from, to), subset.distance(from, to));
+        }
+    }
+
+    let mut far = graph.clone();
+    far.insert_weighted_edge(&d, &e, u32::MAX);
+    let all = DistanceMatrix::all_pairs(&far);
+    assert_eq!(Some(u32::MAX), all.distance(&d, &e));
+    assert_eq!(None, all.distance(&c, &e));
+    assert_eq!(all.distance(&c, &e), DistanceMatrix::between(&far, &[c.clone(), e.clone()]).distance(&c, &e));
+}
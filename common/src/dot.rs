@@ -0,0 +1,656 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Write};
+use std::fs::{self, File};
+use std::hash::Hash;
+use std::io::Write as IoWrite;
+use crate::{Graph, NodeId, Weight};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for DotParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for DotParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Id(String),
+    Arrow,
+    //  `--` of undirected graphs
+    Line,
+    Equals,
+    Separator,
+    Open(char),
+    Close(char),
+}
+
+struct Tokens {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+//  Besides DOT identifiers and numerals also accepts paths like "/a/b.txt" unquoted
+fn is_id_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '.' | '/')
+}
+
+fn tokenize(s: &str) -> Result<Tokens, DotParseError> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut chars = s.chars().peekable();
+    let error = |line, message: &str| DotParseError { line, message: String::from(message) };
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\n' => line += 1,
+            _ if ch.is_whitespace() => {}
+            '#' => {
+                while chars.next_if(|ch| *ch != '\n').is_some() {}
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|ch| *ch != '\n').is_some() {}
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let start = line;
+                loop {
+                    match chars.next() {
+                        Some('*') if chars.peek() == Some(&'/') => {
+                            chars.next();
+                            break;
+                        }
+                        Some('\n') => line += 1,
+                        Some(_) => {}
+                        None => return Err(error(start, "unterminated comment")),
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push((Token::Arrow, line));
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                tokens.push((Token::Line, line));
+            }
+            '=' => tokens.push((Token::Equals, line)),
+            ';' | ',' => tokens.push((Token::Separator, line)),
+            '{' | '[' => tokens.push((Token::Open(ch), line)),
+            '}' | ']' => tokens.push((Token::Close(ch), line)),
+            '"' => {
+                let start = line;
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') if matches!(chars.peek(), Some('"' | '\\')) => id.push(chars.next().expect("illegal state")),
+                        Some('"') => break,
+                        Some(ch) => {
+                            if ch == '\n' {
+                                line += 1;
+                            }
+                            id.push(ch);
+                        }
+                        None => return Err(error(start, "unterminated string")),
+                    }
+                }
+                tokens.push((Token::Id(id), start));
+            }
+            _ if is_id_char(ch) || ch == '-' => {
+                let mut id = String::from(ch);
+                while let Some(ch) = chars.next_if(|ch| is_id_char(*ch)) {
+                    id.push(ch);
+                }
+                tokens.push((Token::Id(id), line));
+            }
+            _ => return Err(error(line, &format!("unexpected character '{}'", ch))),
+        }
+    }
+
+    Ok(Tokens { tokens, position: 0 })
+}
+
+impl Tokens {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens.get(self.position)
+            .or(self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, DotParseError> {
+        Err(DotParseError { line: self.line(), message: String::from(message) })
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+
+    fn next_if(&mut self, expected: &Token) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: &Token, message: &str) -> Result<(), DotParseError> {
+        if self.next_if(expected) { Ok(()) } else { self.error(message) }
+    }
+
+    fn id(&mut self) -> Result<String, DotParseError> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.position += 1;
+                Ok(id)
+            }
+            _ => self.error("expected identifier"),
+        }
+    }
+
+    //  One or more `[a=b, c=d]` lists
+    fn attributes(&mut self) -> Result<Vec<(String, String)>, DotParseError> {
+        let mut attributes = Vec::new();
+        while self.next_if(&Token::Open('[')) {
+            while !self.next_if(&Token::Close(']')) {
+                let name = self.id()?;
+                self.expect(&Token::Equals, "expected '='")?;
+                attributes.push((name, self.id()?));
+                self.next_if(&Token::Separator);
+            }
+        }
+        Ok(attributes)
+    }
+
+    //  A node id or a `{a; b}` group of node ids
+    fn operand(&mut self) -> Result<Vec<String>, DotParseError> {
+        if !self.next_if(&Token::Open('{')) {
+            return Ok(vec![self.id()?]);
+        }
+        let mut ids = Vec::new();
+        while !self.next_if(&Token::Close('}')) {
+            ids.push(self.id()?);
+            self.next_if(&Token::Separator);
+        }
+        Ok(ids)
+    }
+}
+
+//  Splits decorator text like `,fillcolor="green",style="filled"` into name and value pairs
+pub(crate) fn parse_attribute_text(s: &str) -> Result<Vec<(String, String)>, DotParseError> {
+    let mut tokens = tokenize(s)?;
+    let mut attributes = Vec::new();
+    while tokens.peek().is_some() {
+        if tokens.next_if(&Token::Separator) {
+            continue;
+        }
+        let name = tokens.id()?;
+        tokens.expect(&Token::Equals, "expected '='")?;
+        attributes.push((name, tokens.id()?));
+    }
+    Ok(attributes)
+}
+
+//  Nodes in order of their first appearance, with all attribute lists merged
+#[derive(Default)]
+struct Parsed {
+    directed: bool,
+    nodes: Vec<(String, Vec<(String, String)>)>,
+    //  Position of every id in `nodes`
+    index: HashMap<String, usize>,
+    edges: Vec<(String, String, u32)>,
+}
+
+impl Parsed {
+    fn node(&mut self, id: &str) -> &mut Vec<(String, String)> {
+        let index = match self.index.get(id) {
+            Some(index) => *index,
+            None => {
+                self.index.insert(String::from(id), self.nodes.len());
+                self.nodes.push((String::from(id), Vec::new()));
+                self.nodes.len() - 1
+            }
+        };
+        &mut self.nodes[index].1
+    }
+}
+
+fn parse_statements(tokens: &mut Tokens) -> Result<Parsed, DotParseError> {
+    let mut parsed = Parsed::default();
+
+    if tokens.peek() == Some(&Token::Id(String::from("strict"))) {
+        tokens.next();
+    }
+    parsed.directed = match tokens.next() {
+        Some(Token::Id(kind)) if kind == "digraph" => true,
+        Some(Token::Id(kind)) if kind == "graph" => false,
+        _ => return tokens.error("expected 'digraph' or 'graph'"),
+    };
+    let (op, other) = match parsed.directed {
+        true => (Token::Arrow, "'--' in a digraph"),
+        false => (Token::Line, "'->' in an undirected graph"),
+    };
+    if let Some(Token::Id(_)) = tokens.peek() {
+        tokens.next();
+    }
+    tokens.expect(&Token::Open('{'), "expected '{'")?;
+
+    while !tokens.next_if(&Token::Close('}')) {
+        if tokens.peek().is_none() {
+            return tokens.error("expected '}'");
+        }
+        if tokens.next_if(&Token::Separator) {
+            continue;
+        }
+
+        //  Default attributes for the graph, nodes or edges are ignored
+        if let Some(Token::Id(keyword)) = tokens.peek() {
+            if matches!(keyword.as_str(), "graph" | "node" | "edge") {
+                tokens.next();
+                tokens.attributes()?;
+                continue;
+            }
+        }
+
+        let mut operands = vec![tokens.operand()?];
+        if operands[0].len() == 1 && tokens.next_if(&Token::Equals) {
+            //  Graph attribute like `rankdir=LR`
+            tokens.id()?;
+            continue;
+        }
+        while tokens.next_if(&op) {
+            operands.push(tokens.operand()?);
+        }
+        if matches!(tokens.peek(), Some(Token::Arrow | Token::Line)) {
+            return tokens.error(other);
+        }
+        let attributes = tokens.attributes()?;
+
+        if operands.len() == 1 {
+            for id in &operands[0] {
+                parsed.node(id).extend(attributes.iter().cloned());
+            }
+            continue;
+        }
+
+        let weight = match attributes.iter().rev().find(|(name, _)| name == "weight") {
+            Some((_, weight)) => match weight.parse() {
+                Ok(weight) => weight,
+                Err(_) => return tokens.error(&format!("bad weight \"{}\"", weight)),
+            },
+            None => 1,
+        };
+        for pair in operands.windows(2) {
+            for from in &pair[0] {
+                parsed.node(from);
+                for to in &pair[1] {
+                    parsed.node(to);
+                    parsed.edges.push((from.clone(), to.clone(), weight));
+                }
+            }
+        }
+    }
+
+    if tokens.peek().is_some() {
+        return tokens.error("unexpected input after the graph");
+    }
+    Ok(parsed)
+}
+
+//  Reads a digraph or an undirected graph as written by `create_dot_file`. `node_attr` gets every node
+//  with its attributes in the order they appear, edges take their weight from a `weight` attribute
+pub fn parse_dot<NA, F>(s: &str, mut node_attr: F) -> Result<Graph<NA>, DotParseError>
+    where NA: Clone, F: FnMut(&NodeId, &[(String, String)]) -> Option<NA> {
+    let parsed = parse_statements(&mut tokenize(s)?)?;
+
+    let mut graph = if parsed.directed { Graph::new() } else { Graph::undirected() };
+    for (id, attributes) in &parsed.nodes {
+        let node = NodeId::from(id);
+        let attr = node_attr(&node, attributes);
+        graph.insert_node(&node, attr);
+    }
+    for (from, to, weight) in &parsed.edges {
+        graph.insert_weighted_edge(&NodeId::from(from), &NodeId::from(to), *weight);
+    }
+    Ok(graph)
+}
+
+pub fn read_dot_file<NA, F>(filename: &str, node_attr: F) -> Result<Graph<NA>, Box<dyn Error>>
+    where NA: Clone, F: FnMut(&NodeId, &[(String, String)]) -> Option<NA> {
+    let s = fs::read_to_string(filename)?;
+    Ok(parse_dot(&s, node_attr)?)
+}
+
+type Attributes = Vec<(String, String)>;
+type NodeStyle<'a, NA, K> = Box<dyn Fn(&K, Option<&NA>) -> Attributes + 'a>;
+type EdgeStyle<'a, EA, K> = Box<dyn Fn(&K, &K, &EA) -> Attributes + 'a>;
+
+fn to_attributes<I, N, V>(attributes: I) -> Attributes where I: IntoIterator<Item = (N, V)>, N: Display, V: Display {
+    attributes.into_iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+}
+
+//  Ids are always quoted so keys like `/a/b.txt` or `1-2` are valid DOT as well.
+//  Attribute values keep their backslashes, DOT reads escapes like `\n` in labels
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_attributes(buf: &mut String, attributes: &[(String, String)], raw: &str) {
+    let attributes = attributes.iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, value.replace('"', "\\\"")))
+        .collect::<Vec<_>>();
+    write!(buf, "[{}{}]", attributes.join(","), raw).unwrap();
+}
+
+fn write_edge(buf: &mut String, from: &str, op: &str, to: &str, attributes: &[(String, String)]) {
+    write!(buf, "  {} {} {} ", quote(from), op, quote(to)).unwrap();
+    write_attributes(buf, attributes, "");
+    writeln!(buf).unwrap();
+}
+
+//  Writes a graph as DOT with nodes and edges sorted by id, so the file only changes with the graph.
+//  Undirected graphs are written as `graph` with every edge once
+pub struct DotRenderer<'a, NA, EA = u32, K = NodeId> {
+    node_style: NodeStyle<'a, NA, K>,
+    node_decorator: Option<fn(&NA) -> Option<&str>>,
+    edge_style: EdgeStyle<'a, EA, K>,
+    edge_labels: bool,
+    path: Vec<K>,
+}
+
+impl<NA, EA, K> Default for DotRenderer<'_, NA, EA, K> {
+    fn default() -> Self {
+        DotRenderer {
+            node_style: Box::new(|_, _| Vec::new()),
+            node_decorator: None,
+            edge_style: Box::new(|_, _, _| Vec::new()),
+            edge_labels: false,
+            path: Vec::new(),
+        }
+    }
+}
+
+impl<'a, NA, EA, K> DotRenderer<'a, NA, EA, K> where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
+    pub fn new() -> Self {
+        DotRenderer::default()
+    }
+
+    //  Extra attributes like `("fillcolor", "green")` for each node, after its label
+    pub fn node_style<F, I, N, V>(mut self, style: F) -> Self
+        where F: Fn(&K, Option<&NA>) -> I + 'a, I: IntoIterator<Item = (N, V)>, N: Display, V: Display {
+        self.node_style = Box::new(move |node, attr| to_attributes(style(node, attr)));
+        self
+    }
+
+    //  Decorator text like `,fillcolor="green"`, written as is when it doesn't parse
+    pub(crate) fn node_decorator(mut self, decorator: fn(&NA) -> Option<&str>) -> Self {
+        self.node_decorator = Some(decorator);
+        self
+    }
+
+    pub fn edge_style<F, I, N, V>(mut self, style: F) -> Self
+        where F: Fn(&K, &K, &EA) -> I + 'a, I: IntoIterator<Item = (N, V)>, N: Display, V: Display {
+        self.edge_style = Box::new(move |from, to, attr| to_attributes(style(from, to, attr)));
+        self
+    }
+
+    //  Labels every edge with its weight
+    pub fn edge_labels(mut self, enabled: bool) -> Self {
+        self.edge_labels = enabled;
+        self
+    }
+
+    //  Draws the nodes and steps of the path in red, steps are numbered unless edges show weights.
+    //  Steps that aren't edges of the graph, like moves over a contracted graph, are added as dashed edges
+    pub fn highlight_path(mut self, path: &[K]) -> Self {
+        self.path = path.to_vec();
+        self
+    }
+
+    fn step(&self, graph: &Graph<NA, EA, K>, from: &K, to: &K) -> Option<usize> {
+        self.path.windows(2).position(|step| {
+            (&step[0] == from && &step[1] == to) || (!graph.is_directed() && &step[0] == to && &step[1] == from)
+        })
+    }
+
+    pub fn render(&self, graph: &Graph<NA, EA, K>) -> String {
+        let highlight: Attributes = to_attributes([("color", "red"), ("penwidth", "2")]);
+        let (kind, op) = if graph.is_directed() { ("digraph", "->") } else { ("graph", "--") };
+
+        let mut nodes = graph.node_indices()
+            .map(|u| (graph.key(u).to_string(), u))
+            .collect::<Vec<_>>();
+        nodes.sort();
+
+        let mut buf = String::new();
+        writeln!(buf, "{} G {{", kind).unwrap();
+        for (id, u) in &nodes {
+            let node = graph.key(*u);
+            let attr = graph.attribute_at(*u);
+            let label = format!("{}, {}", id, attr.map(|attr| attr.to_string()).unwrap_or_default());
+            let mut attributes = to_attributes([("label", label)]);
+            attributes.extend((self.node_style)(node, attr));
+            let mut raw = "";
+            if let Some(decoration) = attr.zip(self.node_decorator).and_then(|(attr, decorator)| decorator(attr)) {
+                match parse_attribute_text(decoration) {
+                    Ok(decoration) => attributes.extend(decoration),
+                    Err(_) => raw = decoration,
+                }
+            }
+            if self.path.contains(node) {
+                attributes.extend(highlight.iter().cloned());
+            }
+            write!(buf, "  {} ", quote(id)).unwrap();
+            write_attributes(&mut buf, &attributes, raw);
+            writeln!(buf).unwrap();
+
+            let mut successors = graph.successors_at(*u).iter()
+                .map(|(v, attr)| (graph.key(*v).to_string(), *v, attr))
+                .filter(|(to_id, _, _)| graph.is_directed() || id <= to_id)
+                .collect::<Vec<_>>();
+            successors.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+            let mut plain = Vec::new();
+            let mut styled = Vec::new();
+            for (to_id, v, attr) in successors {
+                let to = graph.key(v);
+                let weight = attr.weight();
+                let mut attributes = Attributes::new();
+                if weight != 1 {
+                    attributes.push((String::from("weight"), weight.to_string()));
+                }
+                if self.edge_labels {
+                    attributes.push((String::from("label"), weight.to_string()));
+                }
+                attributes.extend((self.edge_style)(node, to, attr));
+                if let Some(step) = self.step(graph, node, to) {
+                    if !self.edge_labels {
+                        attributes.push((String::from("label"), (step + 1).to_string()));
+                    }
+                    attributes.extend(highlight.iter().cloned());
+                }
+                if attributes.is_empty() {
+                    plain.push(to_id);
+                } else {
+                    styled.push((to_id, attributes));
+                }
+            }
+            if !plain.is_empty() {
+                let plain = plain.iter().map(|to_id| quote(to_id)).collect::<Vec<_>>();
+                writeln!(buf, "  {} {} {{{}}}", quote(id), op, plain.join("; ")).unwrap();
+            }
+            for (to_id, attributes) in styled {
+                write_edge(&mut buf, id, op, &to_id, &attributes);
+            }
+        }
+        for (i, step) in self.path.windows(2).enumerate() {
+            if !graph.has_edge(&step[0], &step[1]) {
+                let attributes = to_attributes([("style", String::from("dashed")), ("constraint", String::from("false")), ("label", (i + 1).to_string())]);
+                write_edge(&mut buf, &step[0].to_string(), op, &step[1].to_string(), &[attributes, highlight.clone()].concat());
+            }
+        }
+        write!(buf, "}}").unwrap();
+        buf
+    }
+
+    pub fn print(&self, graph: &Graph<NA, EA, K>) {
+        println!("{}", self.render(graph));
+    }
+
+    pub fn create_file(&self, graph: &Graph<NA, EA, K>, filename: &str) {
+        let mut file = File::create(filename).unwrap();
+        write!(file, "{}", self.render(graph)).unwrap();
+    }
+}
+
+#[test]
+fn test_parse_dot() {
+    let s = r#"
+        strict digraph "tunnels" {
+          rankdir=LR; node [shape=box]
+          // Comment
+          AA [label="AA, rate=0"]
+          AA -> {BB; "C C"}
+          BB [label="BB, rate=13",fillcolor="green"] [style=filled]
+          /* Chain with
+             a weight */
+          BB -> DD -> AA [weight=4]
+        }
+    "#;
+    let rate = |_: &NodeId, attributes: &[(String, String)]| {
+        let (_, label) = attributes.iter().find(|(name, _)| name == "label")?;
+        let (_, rate) = label.split_once("rate=")?;
+        rate.parse::<u32>().ok()
+    };
+    let graph = parse_dot(s, rate).unwrap();
+
+    let [aa, bb, cc, dd] = ["AA", "BB", "C C", "DD"].map(NodeId::from);
+    assert_eq!(vec![&aa, &bb, &cc, &dd], graph.nodes().collect::<Vec<_>>());
+    assert_eq!(Some(&13), graph.attribute(&bb));
+    assert_eq!(None, graph.attribute(&dd));
+    assert_eq!(Some(1), graph.edge_weight(&aa, &cc));
+    assert_eq!(Some(4), graph.edge_weight(&bb, &dd));
+    assert_eq!(Some(4), graph.edge_weight(&dd, &aa));
+    assert_eq!(4, graph.edge_count());
+
+    let error = parse_dot::<(), _>("digraph {\n  a -> \n}", |_, _| None).unwrap_err();
+    assert_eq!("line 3: expected identifier", error.to_string());
+    let error = parse_dot::<(), _>("digraph {\n  a -> b -- c\n}", |_, _| None).unwrap_err();
+    assert_eq!("line 2: '--' in a digraph", error.to_string());
+    let error = parse_dot::<(), _>("graph { a -- b -> c }", |_, _| None).unwrap_err();
+    assert_eq!("line 1: '->' in an undirected graph", error.to_string());
+}
+
+#[test]
+fn test_dot_round_trip() {
+    use crate::build_graph_dot;
+
+    let [a, b, c] = ["a", "b", "c"].map(NodeId::from);
+    let mut graph: Graph<u32> = Graph::from_edges([(a.clone(), b.clone()), (a.clone(), c.clone()), (c.clone(), a.clone())]);
+    graph.insert_node(&b, Some(7));
+
+    let dot = build_graph_dot(&graph, |_| Some(",color=\"red\""));
+    let parsed = parse_dot(&dot, |_, attributes| {
+        let (_, label) = attributes.iter().find(|(name, _)| name == "label")?;
+        label.split_once(", ")?.1.parse().ok()
+    }).unwrap();
+
+    assert_eq!(graph.nodes().collect::<Vec<_>>(), parsed.nodes().collect::<Vec<_>>());
+    assert_eq!(graph.attributes().collect::<Vec<_>>(), parsed.attributes().collect::<Vec<_>>());
+    assert_eq!(graph.edges().collect::<Vec<_>>(), parsed.edges().collect::<Vec<_>>());
+
+    //  Decorator text that isn't a list of attributes is passed through unchanged
+    let dot = build_graph_dot(&graph, |_| Some(", shape=box style"));
+    assert!(dot.contains("  \"b\" [label=\"b, 7\", shape=box style]\n"));
+}
+
+#[test]
+fn test_dot_renderer() {
+    let [a, b, c, d] = ["a", "b", "c", "d"].map(NodeId::from);
+    let mut graph: Graph<u32> = Graph::from_edges([(c.clone(), a.clone()), (a.clone(), c.clone()), (a.clone(), b.clone())]);
+    graph.insert_weighted_edge(&c, &a, 3);
+    graph.insert_node(&d, Some(5));
+
+    let min_rate = 4;
+    let dot = DotRenderer::new()
+        .node_style(|_, rate: Option<&u32>| rate.filter(|rate| **rate >= min_rate).map(|_| ("fillcolor", "green")))
+        .edge_style(|from, _, _| (from == &a).then_some(("style", "bold")))
+        .edge_labels(true)
+        .highlight_path(&[c.clone(), a.clone(), d.clone()])
+        .render(&graph);
+
+    assert_eq!(r#"digraph G {
+  "a" [label="a, ",color="red",penwidth="2"]
+  "a" -> "b" [label="1",style="bold"]
+  "a" -> "c" [label="1",style="bold"]
+  "b" [label="b, "]
+  "c" [label="c, ",color="red",penwidth="2"]
+  "c" -> "a" [weight="3",label="3",color="red",penwidth="2"]
+  "d" [label="d, 5",fillcolor="green",color="red",penwidth="2"]
+  "a" -> "d" [style="dashed",constraint="false",label="2",color="red",penwidth="2"]
+}"#, dot);
+
+    let plain = DotRenderer::<u32>::new().render(&graph);
+    assert!(plain.contains("  \"a\" -> {\"b\"; \"c\"}\n"));
+    assert_eq!(Some(3), parse_dot::<(), _>(&plain, |_, _| None).unwrap().edge_weight(&c, &a));
+}
+
+#[test]
+fn test_dot_renderer_undirected() {
+    let [a, b, c] = ["a", "b", "c"].map(NodeId::from);
+    let mut graph: Graph<u32> = Graph::undirected();
+    for node in [&c, &b, &a] {
+        graph.insert_node(node, None);
+    }
+    graph.insert_edge(&b, &a);
+    graph.insert_weighted_edge(&c, &b, 2);
+
+    assert_eq!(r#"graph G {
+  "a" [label="a, ",color="red",penwidth="2"]
+  "a" -- "b" [label="1",color="red",penwidth="2"]
+  "b" [label="b, ",color="red",penwidth="2"]
+  "b" -- "c" [weight="2",label="2",color="red",penwidth="2"]
+  "c" [label="c, ",color="red",penwidth="2"]
+}"#, DotRenderer::new().highlight_path(&[a.clone(), b.clone(), c.clone()]).render(&graph));
+
+    let parsed = parse_dot::<(), _>(&DotRenderer::new().render(&graph), |_, _| None).unwrap();
+    assert!(!parsed.is_directed());
+    assert_eq!(vec![&a, &b, &c], parsed.nodes().collect::<Vec<_>>());
+    assert_eq!(2, parsed.edge_count());
+    assert_eq!(Some(1), parsed.edge_weight(&a, &b));
+    assert_eq!(Some(2), parsed.edge_weight(&b, &c));
+    assert_eq!(Some(2), parsed.edge_weight(&c, &b));
+}
+
+#[test]
+fn test_dot_renderer_quotes_ids() {
+    use crate::NodePath;
+
+    let [root, dir, file] = ["/", "/a", "/a/b"].map(NodePath::from);
+    let graph: Graph<u32, u32, NodePath> = Graph::from_edges([(root.clone(), dir.clone()), (dir.clone(), file.clone())]);
+    let dot = DotRenderer::new().highlight_path(&[root.clone(), file.clone()]).render(&graph);
+    assert!(dot.contains("  \"/a\" -> {\"/a/b\"}\n"));
+    assert!(dot.contains("  \"/\" -> \"/a/b\" [style=\"dashed\""));
+
+    let [odd, other] = ["1-2 \"x\"", "c:\\d"].map(NodeId::from);
+    let graph: Graph<u32> = Graph::from_edges([(odd.clone(), other.clone())]);
+    let dot = DotRenderer::new().render(&graph);
+    assert!(dot.contains(r#"  "1-2 \"x\"" -> {"c:\\d"}"#));
+    let parsed = parse_dot::<(), _>(&dot, |_, _| None).unwrap();
+    assert_eq!(vec![&odd, &other], parsed.nodes().collect::<Vec<_>>());
+    assert!(parsed.has_edge(&odd, &other));
+}
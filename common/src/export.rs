@@ -0,0 +1,246 @@
+use std::fmt::{Display, Write};
+use std::fs::File;
+use std::hash::Hash;
+use std::io::Write as IoWrite;
+use crate::dot::parse_attribute_text;
+use crate::{build_graph_dot, Graph, Weight};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+    GraphMl,
+    Json,
+    PlantUml,
+}
+
+impl GraphFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Mermaid => "mmd",
+            GraphFormat::GraphMl => "graphml",
+            GraphFormat::Json => "json",
+            GraphFormat::PlantUml => "puml",
+        }
+    }
+}
+
+//  Node as every format sees it. Decorator attributes use DOT names like `fillcolor`
+//  and are translated by each format where it has an equivalent
+struct ExportNode {
+    id: String,
+    label: String,
+    attributes: Vec<(String, String)>,
+}
+
+fn export_nodes<NA, EA, K>(graph: &Graph<NA, EA, K>, node_decorator: fn(&NA) -> Option<&str>) -> Vec<ExportNode>
+    where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
+    graph.nodes()
+        .map(|node| {
+            let attr = graph.attribute(node);
+            let decoration = attr.and_then(node_decorator).unwrap_or("");
+            ExportNode {
+                id: node.to_string(),
+                label: format!("{}, {}", node, attr.map(|attr| attr.to_string()).unwrap_or_default()),
+                //  Formats without raw DOT text keep text that doesn't parse as a whole
+                attributes: parse_attribute_text(decoration)
+                    .unwrap_or_else(|_| vec![(String::from("decoration"), String::from(decoration))]),
+            }
+        })
+        .collect()
+}
+
+pub fn build_graph<NA, EA, K>(graph: &Graph<NA, EA, K>, format: GraphFormat, node_decorator: fn(&NA) -> Option<&str>) -> String
+    where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
+    match format {
+        GraphFormat::Dot => build_graph_dot(graph, node_decorator),
+        GraphFormat::Mermaid => build_graph_mermaid(graph, node_decorator),
+        GraphFormat::GraphMl => build_graph_graphml(graph, node_decorator),
+        GraphFormat::Json => build_graph_json(graph, node_decorator),
+        GraphFormat::PlantUml => format!("@startuml\n{}\n@enduml\n", build_graph_dot(graph, node_decorator)),
+    }
+}
+
+pub fn create_graph_file<NA, EA, K>(graph: &Graph<NA, EA, K>, format: GraphFormat, node_decorator: fn(&NA) -> Option<&str>, filename: &str)
+    where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
+    let buf = build_graph(graph, format, node_decorator);
+    let mut file = File::create(filename).unwrap();
+    write!(file, "{}", buf).unwrap();
+}
+
+//  Nodes are named by index because Mermaid ids can't contain most punctuation
+fn build_graph_mermaid<NA, EA, K>(graph: &Graph<NA, EA, K>, node_decorator: fn(&NA) -> Option<&str>) -> String
+    where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
+    let mut buf = String::new();
+    writeln!(buf, "flowchart LR").unwrap();
+    for (u, node) in graph.node_indices().zip(export_nodes(graph, node_decorator)) {
+        writeln!(buf, "  n{}[\"{}\"]", u, node.label.replace('"', "#quot;")).unwrap();
+        let style = node.attributes.iter()
+            .filter_map(|(name, value)| match name.as_str() {
+                "fillcolor" => Some(format!("fill:{}", value)),
+                "fontcolor" => Some(format!("color:{}", value)),
+                "color" => Some(format!("stroke:{}", value)),
+                _ => None
+            })
+            .collect::<Vec<_>>();
+        if !style.is_empty() {
+            writeln!(buf, "  style n{} {}", u, style.join(",")).unwrap();
+        }
+    }
+    let link = if graph.is_directed() { "-->" } else { "---" };
+    for u in graph.node_indices() {
+        for (v, weight) in graph.weighted_successors_at(u).filter(|(v, _)| graph.is_directed() || u <= *v) {
+            match weight {
+                1 => writeln!(buf, "  n{} {} n{}", u, link, v).unwrap(),
+                _ => writeln!(buf, "  n{} {}|{}| n{}", u, link, weight, v).unwrap(),
+            }
+        }
+    }
+    buf
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn build_graph_graphml<NA, EA, K>(graph: &Graph<NA, EA, K>, node_decorator: fn(&NA) -> Option<&str>) -> String
+    where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
+    let nodes = export_nodes(graph, node_decorator);
+    let mut names: Vec<&str> = nodes.iter()
+        .flat_map(|node| node.attributes.iter().map(|(name, _)| name.as_str()))
+        .filter(|name| *name != "label")
+        .collect();
+    names.sort();
+    names.dedup();
+
+    let mut buf = String::new();
+    writeln!(buf, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+    writeln!(buf, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">").unwrap();
+    //  Key ids are prefixed by domain, a node attribute may have the same name as an edge one
+    writeln!(buf, "  <key id=\"n_label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>").unwrap();
+    for name in &names {
+        writeln!(buf, "  <key id=\"n_{0}\" for=\"node\" attr.name=\"{0}\" attr.type=\"string\"/>", escape_xml(name)).unwrap();
+    }
+    writeln!(buf, "  <key id=\"e_weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"int\"/>").unwrap();
+    let edge_default = if graph.is_directed() { "directed" } else { "undirected" };
+    writeln!(buf, "  <graph id=\"G\" edgedefault=\"{}\">", edge_default).unwrap();
+    for node in &nodes {
+        writeln!(buf, "    <node id=\"{}\">", escape_xml(&node.id)).unwrap();
+        writeln!(buf, "      <data key=\"n_label\">{}</data>", escape_xml(&node.label)).unwrap();
+        for (name, value) in node.attributes.iter().filter(|(name, _)| name != "label") {
+            writeln!(buf, "      <data key=\"n_{}\">{}</data>", escape_xml(name), escape_xml(value)).unwrap();
+        }
+        writeln!(buf, "    </node>").unwrap();
+    }
+    for (from, to, attr) in graph.edges() {
+        writeln!(buf, "    <edge source=\"{}\" target=\"{}\">", escape_xml(&from.to_string()), escape_xml(&to.to_string())).unwrap();
+        writeln!(buf, "      <data key=\"e_weight\">{}</data>", attr.weight()).unwrap();
+        writeln!(buf, "    </edge>").unwrap();
+    }
+    writeln!(buf, "  </graph>").unwrap();
+    writeln!(buf, "</graphml>").unwrap();
+    buf
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ if (ch as u32) < 0x20 => write!(escaped, "\\u{:04x}", ch as u32).unwrap(),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn build_graph_json<NA, EA, K>(graph: &Graph<NA, EA, K>, node_decorator: fn(&NA) -> Option<&str>) -> String
+    where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
+    let nodes = export_nodes(graph, node_decorator)
+        .iter()
+        .map(|node| {
+            let attributes = node.attributes.iter()
+                .map(|(name, value)| format!("{}: {}", escape_json(name), escape_json(value)))
+                .collect::<Vec<_>>();
+            format!("    {{\"id\": {}, \"label\": {}, \"attributes\": {{{}}}}}",
+                    escape_json(&node.id), escape_json(&node.label), attributes.join(", "))
+        })
+        .collect::<Vec<_>>();
+    let edges = graph.edges()
+        .map(|(from, to, attr)| format!("    {{\"from\": {}, \"to\": {}, \"weight\": {}}}",
+                                        escape_json(&from.to_string()), escape_json(&to.to_string()), attr.weight()))
+        .collect::<Vec<_>>();
+
+    let mut buf = String::new();
+    writeln!(buf, "{{").unwrap();
+    writeln!(buf, "  \"directed\": {},", graph.is_directed()).unwrap();
+    writeln!(buf, "  \"nodes\": [\n{}\n  ],", nodes.join(",\n")).unwrap();
+    writeln!(buf, "  \"edges\": [\n{}\n  ]", edges.join(",\n")).unwrap();
+    writeln!(buf, "}}").unwrap();
+    buf
+}
+
+#[test]
+fn test_export_formats() {
+    use crate::NodeId;
+
+    let [a, b] = ["a", "b"].map(NodeId::from);
+    let mut graph: Graph<u32> = Graph::from_edges([(a.clone(), b.clone())]);
+    graph.insert_node(&b, Some(3));
+    graph.insert_weighted_edge(&b, &a, 2);
+    let decorator: fn(&u32) -> Option<&str> = |rate| match rate {
+        0 => None,
+        _ => Some(",fillcolor=\"green\",style=\"filled\"")
+    };
+
+    assert_eq!("\
+flowchart LR
+  n0[\"a, \"]
+  n1[\"b, 3\"]
+  style n1 fill:green
+  n0 --> n1
+  n1 -->|2| n0
+", build_graph(&graph, GraphFormat::Mermaid, decorator));
+
+    let graphml = build_graph(&graph, GraphFormat::GraphMl, decorator);
+    assert!(graphml.contains("<key id=\"n_fillcolor\" for=\"node\" attr.name=\"fillcolor\" attr.type=\"string\"/>"));
+    assert!(graphml.contains("    <node id=\"b\">\n      <data key=\"n_label\">b, 3</data>\n      <data key=\"n_fillcolor\">green</data>"));
+    assert!(graphml.contains("    <edge source=\"b\" target=\"a\">\n      <data key=\"e_weight\">2</data>"));
+
+    let weighted: fn(&u32) -> Option<&str> = |_| Some(",weight=\"5\"");
+    let graphml = build_graph(&graph, GraphFormat::GraphMl, weighted);
+    assert_eq!(1, graphml.matches("<key id=\"n_weight\" for=\"node\" attr.name=\"weight\"").count());
+    assert_eq!(1, graphml.matches("<key id=\"e_weight\" for=\"edge\" attr.name=\"weight\"").count());
+    assert!(graphml.contains("      <data key=\"n_weight\">5</data>"));
+
+    assert_eq!("\
+{
+  \"directed\": true,
+  \"nodes\": [
+    {\"id\": \"a\", \"label\": \"a, \", \"attributes\": {}},
+    {\"id\": \"b\", \"label\": \"b, 3\", \"attributes\": {\"fillcolor\": \"green\", \"style\": \"filled\"}}
+  ],
+  \"edges\": [
+    {\"from\": \"a\", \"to\": \"b\", \"weight\": 1},
+    {\"from\": \"b\", \"to\": \"a\", \"weight\": 2}
+  ]
+}
+", build_graph(&graph, GraphFormat::Json, decorator));
+
+    let plantuml = build_graph(&graph, GraphFormat::PlantUml, decorator);
+    assert!(plantuml.starts_with("@startuml\ndigraph G {\n"));
+    assert!(plantuml.ends_with("}\n@enduml\n"));
+    assert!(plantuml.contains("\"b\" [label=\"b, 3\",fillcolor=\"green\",style=\"filled\"]"));
+
+    let malformed: fn(&u32) -> Option<&str> = |_| Some(",fillcolor");
+    assert!(build_graph(&graph, GraphFormat::Json, malformed).contains("\"attributes\": {\"decoration\": \",fillcolor\"}"));
+    assert!(build_graph(&graph, GraphFormat::Mermaid, malformed).ends_with("  n1[\"b, 3\"]\n  n0 --> n1\n  n1 -->|2| n0\n"));
+}
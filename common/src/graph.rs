@@ -0,0 +1,499 @@
+use std::hash::Hash;
+use crate::{dijkstra_with, NodeId};
+use crate::persistent::{PMap, PVec};
+
+//  Edge attribute that shortest path searches take the length of an edge from
+pub trait Weight: Clone {
+    fn weight(&self) -> u32;
+    //  Attribute of edges inserted without one
+    fn unit() -> Self;
+}
+
+impl Weight for u32 {
+    fn weight(&self) -> u32 {
+        *self
+    }
+    fn unit() -> Self {
+        1
+    }
+}
+
+impl Weight for () {
+    fn weight(&self) -> u32 {
+        1
+    }
+    fn unit() -> Self {}
+}
+
+//  Graph with typed node and edge attributes. Node keys are interned to dense u32 indices that stay
+//  stable: a removed node leaves a tombstone and gets its index back when inserted again.
+//  An undirected graph keeps every edge in both directions with the same attribute.
+//  Storage is persistent, so clones are cheap and share everything an update doesn't touch.
+//  It is shared through `Arc`, graphs are `Send` and `Sync` whenever their contents are
+#[derive(Debug, Clone)]
+pub struct Graph<NA, EA = u32, K = NodeId> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    keys: PVec<K>,
+    index: PMap<K, u32>,
+    live: PVec<bool>,
+    attributes: PVec<Option<NA>>,
+    //  Outgoing (to, attribute) and incoming adjacency by index
+    edges_from: PVec<Vec<(u32, EA)>>,
+    edges_to: PVec<Vec<u32>>,
+    directed: bool,
+    node_count: usize,
+    edge_count: usize,
+}
+
+impl<NA, EA, K> Default for Graph<NA, EA, K> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    fn default() -> Self {
+        Graph::new()
+    }
+}
+
+impl<NA, EA, K> Graph<NA, EA, K> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    pub fn new() -> Graph<NA, EA, K> {
+        Graph {
+            keys: PVec::new(),
+            index: PMap::new(),
+            live: PVec::new(),
+            attributes: PVec::new(),
+            edges_from: PVec::new(),
+            edges_to: PVec::new(),
+            directed: true,
+            node_count: 0,
+            edge_count: 0,
+        }
+    }
+
+    pub fn undirected() -> Graph<NA, EA, K> {
+        Graph { directed: false, ..Graph::new() }
+    }
+
+    //  Nodes are created without attributes
+    pub fn from_edges<I>(edges: I) -> Graph<NA, EA, K> where I: IntoIterator<Item=(K, K)>, EA: Weight {
+        let mut graph = Graph::new();
+        for (from, to) in edges {
+            graph.insert_node(&from, None);
+            graph.insert_node(&to, None);
+            graph.insert_edge(&from, &to);
+        }
+        graph
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    //  An undirected edge counts once
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.node_count == 0
+    }
+
+    //  Upper bound of all node indices, for sizing index-addressed arrays
+    pub fn index_bound(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn index_of(&self, node: &K) -> Option<u32> {
+        self.index.get(node).copied().filter(|u| self.live[*u as usize])
+    }
+
+    pub fn key(&self, index: u32) -> &K {
+        &self.keys[index as usize]
+    }
+
+    pub fn contains_node(&self, node: &K) -> bool {
+        self.index_of(node).is_some()
+    }
+
+    //  In index order, which is the order nodes were first inserted in
+    pub fn node_indices(&self) -> impl Iterator<Item=u32> + '_ {
+        (0..self.keys.len() as u32).filter(|u| self.live[*u as usize])
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item=&K> + '_ {
+        self.node_indices().map(|u| self.key(u))
+    }
+
+    pub fn attribute(&self, node: &K) -> Option<&NA> {
+        self.attribute_at(self.index_of(node)?)
+    }
+
+    pub fn attribute_at(&self, index: u32) -> Option<&NA> {
+        self.attributes[index as usize].as_ref()
+    }
+
+    //  Nodes without an attribute are skipped
+    pub fn attributes(&self) -> impl Iterator<Item=(&K, &NA)> + '_ {
+        self.node_indices().filter_map(|u| Some((self.key(u), self.attribute_at(u)?)))
+    }
+
+    //  Outgoing (to, attribute) pairs
+    pub fn successors_at(&self, index: u32) -> &[(u32, EA)] {
+        &self.edges_from[index as usize]
+    }
+
+    //  Outgoing (to, weight) pairs
+    pub fn weighted_successors_at(&self, index: u32) -> impl Iterator<Item=(u32, u32)> + '_ where EA: Weight {
+        self.successors_at(index).iter().map(|(v, attr)| (*v, attr.weight()))
+    }
+
+    pub fn predecessors_at(&self, index: u32) -> &[u32] {
+        &self.edges_to[index as usize]
+    }
+
+    pub fn successors<'a>(&'a self, node: &K) -> impl Iterator<Item=&'a K> + 'a {
+        self.index_of(node).into_iter()
+            .flat_map(|u| self.successors_at(u).iter().map(|(v, _)| self.key(*v)))
+    }
+
+    pub fn predecessors<'a>(&'a self, node: &K) -> impl Iterator<Item=&'a K> + 'a {
+        self.index_of(node).into_iter()
+            .flat_map(|u| self.predecessors_at(u).iter().map(|p| self.key(*p)))
+    }
+
+    //  Undirected edges are listed once, from the node with the lower index
+    pub fn edges(&self) -> impl Iterator<Item=(&K, &K, &EA)> + '_ {
+        self.node_indices().flat_map(move |u| {
+            self.successors_at(u).iter()
+                .filter(move |(v, _)| self.directed || u <= *v)
+                .map(move |(v, attr)| (self.key(u), self.key(*v), attr))
+        })
+    }
+
+    pub fn edge_attribute(&self, from: &K, to: &K) -> Option<&EA> {
+        let (u, v) = (self.index_of(from)?, self.index_of(to)?);
+        self.successors_at(u).iter().find(|(to, _)| *to == v).map(|(_, attr)| attr)
+    }
+
+    pub fn edge_weight(&self, from: &K, to: &K) -> Option<u32> where EA: Weight {
+        self.edge_attribute(from, to).map(Weight::weight)
+    }
+
+    pub fn has_edge(&self, from: &K, to: &K) -> bool {
+        self.edge_attribute(from, to).is_some()
+    }
+
+    pub fn add_node(&self, node: &K, attr: Option<NA>) -> Graph<NA, EA, K> {
+        let mut graph = self.clone();
+        graph.insert_node(node, attr);
+        graph
+    }
+
+    pub fn add_edge(&self, from: &K, to: &K) -> Graph<NA, EA, K> where EA: Weight {
+        let mut graph = self.clone();
+        graph.insert_edge(from, to);
+        graph
+    }
+
+    //  Existing attribute is kept when `attr` is None
+    pub fn insert_node(&mut self, node: &K, attr: Option<NA>) -> u32 {
+        let u = match self.index.get(node) {
+            Some(u) => *u,
+            None => {
+                let u = self.keys.len() as u32;
+                self.keys.push(node.clone());
+                self.index.insert(node.clone(), u);
+                self.live.push(false);
+                self.attributes.push(None);
+                self.edges_from.push(Vec::new());
+                self.edges_to.push(Vec::new());
+                u
+            }
+        };
+        if !self.live[u as usize] {
+            self.live[u as usize] = true;
+            self.node_count += 1;
+        }
+        if attr.is_some() {
+            self.attributes[u as usize] = attr;
+        }
+        u
+    }
+
+    pub fn insert_edge(&mut self, from: &K, to: &K) -> bool where EA: Weight {
+        self.insert_edge_with(from, to, EA::unit())
+    }
+
+    //  Re-inserting an existing edge replaces its attribute
+    pub fn insert_edge_with(&mut self, from: &K, to: &K, attr: EA) -> bool {
+        let u = self.index_of(from).expect("no such node");
+        let v = self.index_of(to).expect("no such node");
+
+        if !self.directed && u != v {
+            self.link(v, u, attr.clone());
+        }
+        let inserted = self.link(u, v, attr);
+        if inserted {
+            self.edge_count += 1;
+        }
+        inserted
+    }
+
+    fn link(&mut self, u: u32, v: u32, attr: EA) -> bool {
+        let edges_from = &mut self.edges_from[u as usize];
+        if let Some(edge) = edges_from.iter_mut().find(|(to, _)| *to == v) {
+            edge.1 = attr;
+            return false;
+        }
+        edges_from.push((v, attr));
+        self.edges_to[v as usize].push(u);
+        true
+    }
+
+    pub fn remove_edge(&mut self, from: &K, to: &K) -> bool {
+        let (Some(u), Some(v)) = (self.index_of(from), self.index_of(to)) else {
+            return false;
+        };
+        if !self.unlink(u, v) {
+            return false;
+        }
+        if !self.directed && u != v {
+            self.unlink(v, u);
+        }
+        self.edge_count -= 1;
+        true
+    }
+
+    fn unlink(&mut self, u: u32, v: u32) -> bool {
+        let edges_from = &mut self.edges_from[u as usize];
+        let Some(position) = edges_from.iter().position(|(to, _)| *to == v) else {
+            return false;
+        };
+        edges_from.remove(position);
+        let edges_to = &mut self.edges_to[v as usize];
+        let position = edges_to.iter().position(|from| *from == u).expect("illegal state");
+        edges_to.remove(position);
+        true
+    }
+
+    //  Also removes all incoming and outgoing edges of the node
+    pub fn remove_node(&mut self, node: &K) -> bool {
+        let Some(u) = self.index_of(node) else {
+            return false;
+        };
+        let edges_from = std::mem::take(&mut self.edges_from[u as usize]);
+        for (v, _) in &edges_from {
+            self.edges_to[*v as usize].retain(|from| *from != u);
+        }
+        let edges_to = std::mem::take(&mut self.edges_to[u as usize]);
+        for p in &edges_to {
+            self.edges_from[*p as usize].retain(|(to, _)| *to != u);
+        }
+        //  A self loop was already dropped from the incoming list above,
+        //  undirected edges are in both lists
+        self.edge_count -= match self.directed {
+            true => edges_from.len() + edges_to.len(),
+            false => edges_from.len(),
+        };
+        self.live[u as usize] = false;
+        self.attributes[u as usize] = None;
+        self.node_count -= 1;
+        true
+    }
+
+    //  Removes the nodes matching `remove` and replaces every path that only passes through
+    //  removed nodes with a single edge weighted by the path length, keeping the shortest one
+    pub fn contract<P>(&self, remove: P) -> Graph<NA, u32, K> where P: Fn(&K, Option<&NA>) -> bool, EA: Weight {
+        let mut removed = vec![false; self.index_bound()];
+        for u in self.node_indices() {
+            removed[u as usize] = remove(self.key(u), self.attribute_at(u));
+        }
+
+        let mut graph = if self.directed { Graph::new() } else { Graph::undirected() };
+        for u in self.node_indices().filter(|u| !removed[*u as usize]) {
+            graph.insert_node(self.key(u), self.attribute_at(u).cloned());
+        }
+
+        for from in self.node_indices().filter(|u| !removed[*u as usize]) {
+            let (dist, _) = dijkstra_with([from], |u: &u32| {
+                //  Don't walk past remaining nodes, except the one we start from
+                let passable = *u == from || removed[*u as usize];
+                self.weighted_successors_at(*u).filter(move |_| passable)
+            });
+            for (to, weight) in dist {
+                if to != from && !removed[to as usize] {
+                    graph.insert_weighted_edge(self.key(from), self.key(to), weight);
+                }
+            }
+        }
+        graph
+    }
+}
+
+impl<NA, K> Graph<NA, u32, K> where NA: Clone, K: Clone + Eq + Hash {
+    //  Re-inserting an existing edge replaces its weight
+    pub fn insert_weighted_edge(&mut self, from: &K, to: &K, weight: u32) -> bool {
+        self.insert_edge_with(from, to, weight)
+    }
+}
+
+#[test]
+fn test_graph_mutation() {
+    let [a, b, c] = ["a", "b", "c"].map(NodeId::from);
+
+    let mut graph: Graph<u32> = Graph::from_edges([(a.clone(), b.clone()), (b.clone(), c.clone()), (a.clone(), c.clone())]);
+    graph.insert_node(&a, Some(1));
+    assert_eq!(3, graph.node_count());
+    assert_eq!(Some(2), graph.index_of(&c));
+    assert_eq!(&c, graph.key(2));
+    assert!(!graph.insert_edge(&a, &b));
+    assert_eq!(vec![&b, &a], graph.predecessors(&c).collect::<Vec<_>>());
+
+    let snapshot = graph.add_edge(&c, &a);
+    assert!(!graph.has_edge(&c, &a));
+    assert!(snapshot.has_edge(&c, &a));
+
+    assert!(graph.remove_edge(&b, &c));
+    assert!(!graph.remove_edge(&b, &c));
+    assert_eq!(0, graph.successors(&b).count());
+    assert_eq!(vec![&a], graph.predecessors(&c).collect::<Vec<_>>());
+    assert_eq!(2, graph.edge_count());
+
+    graph.insert_edge(&a, &a);
+    assert!(graph.remove_node(&a));
+    assert_eq!(None, graph.attribute(&a));
+    assert_eq!(0, graph.edge_count());
+    assert_eq!(0, graph.predecessors(&c).count());
+    assert_eq!(vec![&b, &c], graph.nodes().collect::<Vec<_>>());
+
+    //  Tombstoned index is reused
+    assert_eq!(0, graph.insert_node(&a, None));
+    assert_eq!(3, graph.node_count());
+}
+
+#[test]
+fn test_graph_contract() {
+    use crate::{build_graph_dot, dijkstra};
+
+    let [a, b, c, x, y] = ["a", "b", "c", "x", "y"].map(NodeId::from);
+    let mut graph: Graph<u32> = Graph::from_edges([
+        (a.clone(), x.clone()), (x.clone(), y.clone()), (y.clone(), b.clone()), (b.clone(), c.clone()),
+        (y.clone(), x.clone()), (x.clone(), a.clone()),
+    ]);
+    graph.insert_weighted_edge(&a, &b, 5);
+    graph.insert_weighted_edge(&a, &c, 2);
+    for node in [&a, &b, &c] {
+        graph.insert_node(node, Some(1));
+    }
+    graph.insert_node(&x, Some(0));
+
+    let contracted = graph.contract(|_, attr| attr.is_none_or(|rate| *rate == 0));
+    assert_eq!(vec![&a, &b, &c], contracted.nodes().collect::<Vec<_>>());
+    assert_eq!(Some(&1), contracted.attribute(&b));
+    assert_eq!(Some(3), contracted.edge_weight(&a, &b));
+    assert_eq!(Some(2), contracted.edge_weight(&a, &c));
+    assert_eq!(3, contracted.edge_count());
+    assert_eq!(0, contracted.successors(&c).count());
+    assert_eq!(Some(&2), dijkstra(&contracted, &a).0.get(&c));
+    assert!(build_graph_dot(&contracted, |_| None).contains("\"a\" -> \"b\" [weight=\"3\"]"));
+}
+
+#[test]
+fn test_point_keys() {
+    use crate::Point2;
+
+    let origin = Point2::origin();
+    let mut graph: Graph<(), (), Point2> = Graph::new();
+    for point in origin.neighbours(1) {
+        graph.insert_node(&origin, None);
+        graph.insert_node(&point, None);
+        graph.insert_edge(&origin, &point);
+    }
+    assert_eq!(5, graph.node_count());
+    assert_eq!(4, graph.successors(&origin).count());
+    assert_eq!(Some(1), graph.edge_weight(&origin, &Point2::origin().plus_axis(1, 1)));
+}
+
+#[test]
+fn test_undirected_edges() {
+    let [a, b, c] = ["a", "b", "c"].map(NodeId::from);
+
+    let mut graph: Graph<(), &str> = Graph::undirected();
+    for node in [&a, &b, &c] {
+        graph.insert_node(node, None);
+    }
+    assert!(graph.insert_edge_with(&a, &b, "ab"));
+    assert!(!graph.insert_edge_with(&b, &a, "ba"));
+    graph.insert_edge_with(&b, &c, "bc");
+    graph.insert_edge_with(&c, &c, "cc");
+    assert_eq!(3, graph.edge_count());
+    assert_eq!(Some(&"ba"), graph.edge_attribute(&a, &b));
+    assert_eq!(vec![&b], graph.predecessors(&a).collect::<Vec<_>>());
+    assert_eq!(vec![(&a, &b, &"ba"), (&b, &c, &"bc"), (&c, &c, &"cc")], graph.edges().collect::<Vec<_>>());
+
+    assert!(graph.remove_edge(&b, &a));
+    assert!(!graph.has_edge(&a, &b));
+    assert_eq!(0, graph.successors(&a).count());
+    assert!(graph.remove_node(&c));
+    assert_eq!(0, graph.edge_count());
+    assert_eq!(0, graph.successors(&b).count());
+}
+
+#[test]
+fn test_snapshots() {
+    fn edges<NA, EA, K>(graph: &Graph<NA, EA, K>) -> Vec<(K, K, EA)> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+        graph.edges().map(|(from, to, attr)| (from.clone(), to.clone(), attr.clone())).collect()
+    }
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    //  Enough nodes for the storage to be more than one level deep
+    let ids = (0..100).map(|i| NodeId::from(&i.to_string())).collect::<Vec<_>>();
+    let mut graph: Graph<u32> = Graph::from_edges(ids.windows(2).map(|pair| (pair[0].clone(), pair[1].clone())));
+    graph.insert_node(&ids[50], Some(50));
+    let snapshot = graph.clone();
+    assert_send_sync(&snapshot);
+    let (nodes, snapshot_edges) = (snapshot.nodes().cloned().collect::<Vec<_>>(), edges(&snapshot));
+
+    let new = NodeId::from("new");
+    let grown = graph.add_node(&new, Some(1)).add_edge(&ids[99], &new);
+    graph.insert_weighted_edge(&ids[0], &ids[99], 5);
+    graph.insert_node(&ids[50], Some(0));
+    graph.remove_edge(&ids[10], &ids[11]);
+    //  A removed node gets its old index back when it is inserted again
+    graph.remove_node(&ids[20]);
+    assert_eq!(None, graph.index_of(&ids[20]));
+    assert_eq!(20, graph.insert_node(&ids[20], Some(7)));
+
+    assert_eq!(101, grown.node_count());
+    assert!(grown.has_edge(&ids[99], &new));
+    assert_eq!(Some(5), graph.edge_weight(&ids[0], &ids[99]));
+    assert!(!graph.has_edge(&ids[10], &ids[11]));
+    assert!(!graph.has_edge(&ids[19], &ids[20]));
+    assert_eq!(Some(&7), graph.attribute(&ids[20]));
+
+    assert_eq!(nodes, snapshot.nodes().cloned().collect::<Vec<_>>());
+    assert_eq!(snapshot_edges, edges(&snapshot));
+    assert_eq!(99, snapshot.edge_count());
+    assert_eq!(Some(&50), snapshot.attribute(&ids[50]));
+    assert_eq!(None, snapshot.attribute(&ids[20]));
+    assert!(snapshot.has_edge(&ids[19], &ids[20]) && snapshot.has_edge(&ids[10], &ids[11]));
+    assert!(!snapshot.contains_node(&new));
+
+    let [a, b, c] = ["a", "b", "c"].map(NodeId::from);
+    let mut graph: Graph<(), u32> = Graph::undirected();
+    for node in [&a, &b, &c] {
+        graph.insert_node(node, None);
+    }
+    graph.insert_weighted_edge(&a, &b, 2);
+    let snapshot = graph.clone();
+    graph.insert_weighted_edge(&b, &c, 3);
+    graph.insert_weighted_edge(&b, &a, 4);
+    graph.remove_node(&a);
+    graph.insert_node(&a, None);
+    graph.insert_edge(&c, &a);
+
+    assert_eq!(vec![(a.clone(), c.clone(), 1), (b.clone(), c.clone(), 3)], edges(&graph));
+    assert_eq!(vec![(a.clone(), b.clone(), 2)], edges(&snapshot));
+    assert_eq!(Some(2), snapshot.edge_weight(&b, &a));
+    assert_eq!(vec![&b], snapshot.predecessors(&a).collect::<Vec<_>>());
+    assert_eq!(0, snapshot.successors(&c).count());
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+use crate::{BoundingBox, Pos};
+
+//  Dense row-major storage for rectangular maps with fixed bounds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    bounds: BoundingBox,
+    width: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(bounds: BoundingBox, value: T) -> Grid<T> where T: Clone {
+        Grid::from_fn(bounds, |_| value.clone())
+    }
+
+    pub fn from_fn<F>(bounds: BoundingBox, mut f: F) -> Grid<T> where F: FnMut(Pos) -> T {
+        assert!(bounds.min_row <= bounds.max_row && bounds.min_col <= bounds.max_col, "bad size");
+        let width = bounds.width() as usize;
+        let height = bounds.height() as usize;
+        let mut cells = Vec::with_capacity(width * height);
+        for row in bounds.min_row..=bounds.max_row {
+            for col in bounds.min_col..=bounds.max_col {
+                cells.push(f(Pos { row, col }));
+            }
+        }
+        Grid { bounds, width, cells }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        assert!(width > 0 && rows.iter().all(|row| row.len() == width), "bad size");
+        let bounds = BoundingBox { min_row: 0, max_row: rows.len() as i32 - 1, min_col: 0, max_col: width as i32 - 1 };
+        Grid { bounds, width, cells: rows.into_iter().flatten().collect() }
+    }
+
+    pub fn from_map(map: &HashMap<Pos, T>, empty: T) -> Grid<T> where T: Clone {
+        Grid::from_fn(BoundingBox::of_map(map).expect("bad data"), |pos| map.get(&pos).unwrap_or(&empty).clone())
+    }
+
+    pub fn to_map(&self) -> HashMap<Pos, T> where T: Clone {
+        self.iter().map(|(pos, value)| (pos, value.clone())).collect()
+    }
+
+    pub fn bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len() / self.width
+    }
+
+    pub fn contains(&self, pos: &Pos) -> bool {
+        self.index_of(pos).is_some()
+    }
+
+    pub fn get(&self, pos: &Pos) -> Option<&T> {
+        self.index_of(pos).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, pos: &Pos) -> Option<&mut T> {
+        self.index_of(pos).map(|index| &mut self.cells[index])
+    }
+
+    pub fn row(&self, row: i32) -> Option<&[T]> {
+        if row < self.bounds.min_row || row > self.bounds.max_row {
+            return None;
+        }
+        let start = (row - self.bounds.min_row) as usize * self.width;
+        Some(&self.cells[start..start + self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item=&[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, col: i32) -> impl Iterator<Item=&T> {
+        let (skip, len) = if col < self.bounds.min_col || col > self.bounds.max_col {
+            (0, 0)
+        } else {
+            ((col - self.bounds.min_col) as usize, self.cells.len())
+        };
+        self.cells[..len].iter().skip(skip).step_by(self.width)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item=Pos> + '_ {
+        (0..self.cells.len()).map(|index| self.pos_of(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(Pos, &T)> {
+        self.cells.iter().enumerate().map(|(index, value)| (self.pos_of(index), value))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item=(Pos, &mut T)> {
+        let bounds = self.bounds;
+        let width = self.width;
+        self.cells.iter_mut().enumerate().map(move |(index, value)| (pos_at(&bounds, width, index), value))
+    }
+
+    fn index_of(&self, pos: &Pos) -> Option<usize> {
+        if pos.row < self.bounds.min_row || pos.row > self.bounds.max_row
+            || pos.col < self.bounds.min_col || pos.col > self.bounds.max_col {
+            return None;
+        }
+        Some((pos.row - self.bounds.min_row) as usize * self.width + (pos.col - self.bounds.min_col) as usize)
+    }
+
+    fn pos_of(&self, index: usize) -> Pos {
+        pos_at(&self.bounds, self.width, index)
+    }
+}
+
+fn pos_at(bounds: &BoundingBox, width: usize, index: usize) -> Pos {
+    Pos {
+        row: bounds.min_row + (index / width) as i32,
+        col: bounds.min_col + (index % width) as i32,
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(&pos).expect("out of bounds")
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        self.get_mut(&pos).expect("out of bounds")
+    }
+}
+
+impl<T> From<&HashMap<Pos, T>> for Grid<Option<T>> where T: Clone {
+    fn from(map: &HashMap<Pos, T>) -> Self {
+        Grid::from_fn(BoundingBox::of_map(map).expect("bad data"), |pos| map.get(&pos).cloned())
+    }
+}
+
+impl<T> Grid<Option<T>> {
+    //  Filled cells only, the inverse of `Grid::from(&map)`
+    pub fn to_filled_map(&self) -> HashMap<Pos, T> where T: Clone {
+        self.iter().filter_map(|(pos, value)| Some((pos, value.clone()?))).collect()
+    }
+}
+
+impl<T> From<Grid<T>> for HashMap<Pos, T> {
+    fn from(grid: Grid<T>) -> Self {
+        let bounds = grid.bounds;
+        let width = grid.width;
+        grid.cells.into_iter().enumerate().map(|(index, value)| (pos_at(&bounds, width, index), value)).collect()
+    }
+}
+
+#[test]
+fn test_grid_access() {
+    let bounds = BoundingBox { min_row: -1, max_row: 1, min_col: 2, max_col: 5 };
+    let mut grid = Grid::from_fn(bounds, |pos| pos.row * 10 + pos.col);
+    assert_eq!(4, grid.width());
+    assert_eq!(3, grid.height());
+    assert_eq!(Some(&-8), grid.get(&Pos { row: -1, col: 2 }));
+    assert_eq!(None, grid.get(&Pos { row: 2, col: 2 }));
+    assert_eq!(None, grid.get(&Pos { row: 0, col: 6 }));
+    grid[Pos { row: 0, col: 3 }] = 42;
+    assert_eq!(Some(&[2, 42, 4, 5][..]), grid.row(0));
+    assert_eq!(vec![&-5, &5, &15], grid.column(5).collect::<Vec<_>>());
+    assert_eq!(0, grid.column(6).count());
+
+    let map: HashMap<Pos, i32> = grid.clone().into();
+    assert_eq!(12, map.len());
+    assert_eq!(grid, Grid::from_map(&map, 0));
+
+    let sparse = HashMap::from([(Pos { row: 0, col: 0 }, 'a'), (Pos { row: 2, col: 1 }, 'b')]);
+    let grid = Grid::from(&sparse);
+    assert_eq!(6, grid.iter().count());
+    assert_eq!(sparse, grid.to_filled_map());
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use crate::{BoundingBox, Pos};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+//  RGB raster where every map cell becomes a `scale` x `scale` block of pixels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn from_fn<F>(bounds: &BoundingBox, scale: usize, color: F) -> Image where F: Fn(Pos) -> Rgb {
+        assert!(scale > 0);
+        let rows = bounds.height() as usize;
+        let width = bounds.width() as usize * scale;
+        let mut pixels = Vec::with_capacity(width * rows * scale * 3);
+        for row in bounds.min_row..=bounds.max_row {
+            let line_start = pixels.len();
+            for col in bounds.min_col..=bounds.max_col {
+                let Rgb(r, g, b) = color(Pos { row, col });
+                for _ in 0..scale {
+                    pixels.extend_from_slice(&[r, g, b]);
+                }
+            }
+            for _ in 1..scale {
+                pixels.extend_from_within(line_start..line_start + width * 3);
+            }
+        }
+        Image { width, height: rows * scale, pixels }
+    }
+
+    //  `color` receives None for positions inside the bounds that are missing in the map
+    pub fn from_map<T, F>(map: &HashMap<Pos, T>, scale: usize, color: F) -> Image where F: Fn(Option<&T>) -> Rgb {
+        Image::from_fn(&BoundingBox::of_map(map).expect("bad data"), scale, |pos| color(map.get(&pos)))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        let offset = (y * self.width + x) * 3;
+        Rgb(self.pixels[offset], self.pixels[offset + 1], self.pixels[offset + 2])
+    }
+
+    pub fn write_ppm<W>(&self, out: &mut W) -> io::Result<()> where W: Write {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.pixels)
+    }
+
+    //  Truecolor PNG with an uncompressed (stored) deflate stream
+    pub fn write_png<W>(&self, out: &mut W) -> io::Result<()> where W: Write {
+        out.write_all(&[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a])?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        //  8 bits per channel, RGB, deflate, no filter, no interlace
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        write_png_chunk(out, b"IHDR", &header)?;
+
+        let mut scanlines = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for line in self.pixels.chunks(self.width * 3) {
+            scanlines.push(0);
+            scanlines.extend_from_slice(line);
+        }
+        write_png_chunk(out, b"IDAT", &zlib_stored(&scanlines))?;
+
+        write_png_chunk(out, b"IEND", &[])
+    }
+
+    pub fn save_ppm(&self, filename: &str) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(filename)?);
+        self.write_ppm(&mut file)?;
+        file.flush()
+    }
+
+    pub fn save_png(&self, filename: &str) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(filename)?);
+        self.write_png(&mut file)?;
+        file.flush()
+    }
+}
+
+fn write_png_chunk<W>(out: &mut W, chunk_type: &[u8; 4], data: &[u8]) -> io::Result<()> where W: Write {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(chunk_type)?;
+    out.write_all(data)?;
+    let crc = crc32(&[&chunk_type[..], data]);
+    out.write_all(&crc.to_be_bytes())
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = 0xffff;
+    let blocks = data.len().div_ceil(MAX_BLOCK).max(1);
+    let mut buf = Vec::with_capacity(data.len() + blocks * 5 + 6);
+    //  Deflate with 32K window, no preset dictionary, fastest compression
+    buf.extend_from_slice(&[0x78, 0x01]);
+    for block in 0..blocks {
+        let chunk = &data[(block * MAX_BLOCK).min(data.len())..((block + 1) * MAX_BLOCK).min(data.len())];
+        let len = chunk.len() as u16;
+        buf.push(if block + 1 == blocks { 1 } else { 0 });
+        buf.extend_from_slice(&len.to_le_bytes());
+        buf.extend_from_slice(&(!len).to_le_bytes());
+        buf.extend_from_slice(chunk);
+    }
+    buf.extend_from_slice(&adler32(data).to_be_bytes());
+    buf
+}
+
+fn crc32(parts: &[&[u8]]) -> u32 {
+    let mut table = [0u32; 256];
+    for (n, entry) in table.iter_mut().enumerate() {
+        let mut c = n as u32;
+        for _ in 0..8 {
+            c = if c & 1 == 1 { 0xedb88320 ^ (c >> 1) } else { c >> 1 };
+        }
+        *entry = c;
+    }
+
+    let mut crc = 0xffffffffu32;
+    for byte in parts.iter().flat_map(|part| part.iter()) {
+        crc = table[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    crc ^ 0xffffffff
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let mut a = 1u32;
+    let mut b = 0u32;
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+#[test]
+fn test_checksums() {
+    assert_eq!(0xae426082, crc32(&[b"IEND"]));
+    assert_eq!(0x11e60398, adler32(b"Wikipedia"));
+}
+
+#[test]
+fn test_image_export() {
+    let map = HashMap::from([
+        (Pos { row: 0, col: 0 }, 'o'),
+        (Pos { row: 1, col: 1 }, '#'),
+    ]);
+    let image = Image::from_map(&map, 2, |cell| match cell {
+        Some('#') => Rgb(255, 0, 0),
+        Some(_) => Rgb(0, 255, 0),
+        None => Rgb(0, 0, 0),
+    });
+    assert_eq!(4, image.width());
+    assert_eq!(4, image.height());
+    assert_eq!(Rgb(0, 255, 0), image.pixel(1, 1));
+    assert_eq!(Rgb(0, 0, 0), image.pixel(2, 1));
+    assert_eq!(Rgb(255, 0, 0), image.pixel(3, 3));
+
+    let mut ppm = Vec::new();
+    image.write_ppm(&mut ppm).unwrap();
+    assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+    assert_eq!(11 + 4 * 4 * 3, ppm.len());
+
+    let mut png = Vec::new();
+    image.write_png(&mut png).unwrap();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x00\x04\x00\x00\x00\x04"));
+    assert!(png.ends_with(b"\x00\x00\x00\x00IEND\xae\x42\x60\x82"));
+}
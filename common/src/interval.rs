@@ -0,0 +1,218 @@
+use std::fmt::Debug;
+use std::ops::{Add, RangeInclusive, Sub};
+
+pub trait Integer: Copy + Ord + Debug + Add<Output=Self> + Sub<Output=Self> {
+    const ZERO: Self;
+    const ONE: Self;
+    fn succ(self) -> Option<Self>;
+    fn pred(self) -> Option<Self>;
+    //  Number of steps between two values, wide enough for any pair of any width
+    fn distance(self, other: Self) -> u128;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            fn succ(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+            fn pred(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+            fn distance(self, other: Self) -> u128 {
+                self.abs_diff(other) as u128
+            }
+        })*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+//  Sorted set of disjoint inclusive intervals, touching intervals are merged
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> where T: Integer {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T> Default for IntervalSet<T> where T: Integer {
+    fn default() -> Self {
+        IntervalSet { ranges: Vec::new() }
+    }
+}
+
+//  True when `end` is followed by a gap before `start`
+fn ends_before<T>(end: T, start: T) -> bool where T: Integer {
+    match end.succ() {
+        Some(next) => next < start,
+        None => false
+    }
+}
+
+impl<T> IntervalSet<T> where T: Integer {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet::default()
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        let from = self.ranges.partition_point(|(_, e)| ends_before(*e, start));
+        let to = self.ranges.partition_point(|(s, _)| !ends_before(end, *s));
+        let merged = match self.ranges[from..to].first() {
+            None => (start, end),
+            Some((first_start, _)) => (start.min(*first_start), end.max(self.ranges[to - 1].1)),
+        };
+        self.ranges.splice(from..to, [merged]);
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.insert(range);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                ranges.push((start, end));
+            }
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for (start, end) in self.ranges.iter().copied() {
+            let mut current = Some(start);
+            while j < other.ranges.len() && other.ranges[j].1 < start {
+                j += 1;
+            }
+            let mut k = j;
+            while let Some(from) = current {
+                match other.ranges.get(k) {
+                    Some((b_start, b_end)) if *b_start <= end => {
+                        if from < *b_start {
+                            ranges.push((from, b_start.pred().expect("illegal state")));
+                        }
+                        current = if *b_end < end { b_end.succ() } else { None };
+                        k += 1;
+                    }
+                    _ => {
+                        ranges.push((from, end));
+                        current = None;
+                    }
+                }
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn clip(&self, window: RangeInclusive<T>) -> IntervalSet<T> {
+        self.intersection(&IntervalSet::from(window))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.contains_range(&(value..=value))
+    }
+
+    pub fn contains_range(&self, range: &RangeInclusive<T>) -> bool {
+        let index = self.ranges.partition_point(|(_, e)| e < range.start());
+        match self.ranges.get(index) {
+            Some((start, end)) => start <= range.start() && range.end() <= end,
+            None => false
+        }
+    }
+
+    pub fn overlaps(&self, range: &RangeInclusive<T>) -> bool {
+        let index = self.ranges.partition_point(|(_, e)| e < range.start());
+        match self.ranges.get(index) {
+            Some((start, _)) => start <= range.end(),
+            None => false
+        }
+    }
+
+    //  Number of covered values, counted in `u128` so a set covering all of `i64` fits as well.
+    //  Only the full domain of `i128` or `u128` doesn't, it saturates at `u128::MAX`
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().fold(0u128, |sum, (start, end)| sum.saturating_add(start.distance(*end)).saturating_add(1))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..=*end)
+    }
+
+    //  Uncovered ranges between the first and the last interval
+    pub fn gaps(&self) -> impl Iterator<Item=RangeInclusive<T>> + '_ {
+        self.ranges.windows(2).map(|pair| {
+            pair[0].1.succ().expect("illegal state")..=pair[1].0.pred().expect("illegal state")
+        })
+    }
+}
+
+impl<T> From<RangeInclusive<T>> for IntervalSet<T> where T: Integer {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T> FromIterator<RangeInclusive<T>> for IntervalSet<T> where T: Integer {
+    fn from_iter<I: IntoIterator<Item=RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[test]
+fn test_interval_set() {
+    let set: IntervalSet<i32> = [5..=7, -2..=0, 1..=2, 10..=12, 11..=15].into_iter().collect();
+    assert_eq!(vec![-2..=2, 5..=7, 10..=15], set.iter().collect::<Vec<_>>());
+    assert_eq!(5 + 3 + 6, set.len());
+    assert_eq!(vec![3..=4, 8..=9], set.gaps().collect::<Vec<_>>());
+    assert!(set.contains(6));
+    assert!(!set.contains(8));
+    assert!(set.contains_range(&(10..=15)));
+    assert!(!set.contains_range(&(7..=10)));
+    assert!(set.overlaps(&(7..=10)));
+    assert!(!set.overlaps(&(8..=9)));
+
+    let other: IntervalSet<i32> = [0..=5, 9..=11, 14..=30].into_iter().collect();
+    assert_eq!(vec![-2..=7, 9..=30], set.union(&other).iter().collect::<Vec<_>>());
+    assert_eq!(vec![0..=2, 5..=5, 10..=11, 14..=15], set.intersection(&other).iter().collect::<Vec<_>>());
+    assert_eq!(vec![-2..=-1, 6..=7, 12..=13], set.difference(&other).iter().collect::<Vec<_>>());
+    assert_eq!(vec![1..=2, 5..=6], set.clip(1..=6).iter().collect::<Vec<_>>());
+
+    let edges: IntervalSet<u8> = [0..=3, 250..=255].into_iter().collect();
+    assert_eq!(vec![4..=249], IntervalSet::from(0..=255).difference(&edges).iter().collect::<Vec<_>>());
+    assert_eq!(10, edges.len());
+
+    assert_eq!(1 << 32, IntervalSet::from(i32::MIN..=i32::MAX).len());
+    assert_eq!(1 << 64, IntervalSet::from(0..=u64::MAX).len());
+    assert_eq!(u128::MAX, IntervalSet::from(i128::MIN..=i128::MAX).len());
+}
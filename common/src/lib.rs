@@ -1,9 +1,48 @@
-use std::collections::{HashMap, HashSet};
-use std::fmt::{Debug, Display, Formatter, Write};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::fmt::{Debug, Display, Formatter};
 use std::fs::File;
 use std::io::Write as IoWrite;
-use std::ops::Add;
-
+use std::ops::{Add, Mul, Neg, Sub};
+
+mod animate;
+mod bounding_box;
+mod distance;
+mod dot;
+mod export;
+mod graph;
+mod grid;
+mod image;
+mod interval;
+mod node_path;
+mod parse;
+mod path;
+mod persistent;
+mod point;
+mod render;
+mod search;
+mod traversal;
+mod tree;
+
+pub use animate::{FrameSink, NoFrames, TerminalPlayer};
+pub use bounding_box::{BoundedMap, BoundingBox};
+pub use distance::DistanceMatrix;
+pub use dot::{DotParseError, DotRenderer, parse_dot, read_dot_file};
+pub use export::{build_graph, create_graph_file, GraphFormat};
+pub use graph::{Graph, Weight};
+pub use grid::Grid;
+pub use image::{Image, Rgb};
+pub use interval::{Integer, IntervalSet};
+pub use node_path::NodePath;
+pub use parse::{GridParseError, Markers, parse_grid};
+pub use path::{all_shortest_paths, PathResult, shortest_path};
+pub use point::{Bounds, ParsePointError, parse_points, Point, Point2, Point3};
+pub use render::MapRenderer;
+pub use search::{astar, astar_graph, dijkstra_until, dijkstra_with, SearchPath};
+pub use traversal::{Bfs, bfs, check_tree, CycleError, Dfs, dfs, reachable, strongly_connected_components, topological_sort, TreeError, weakly_connected_components};
+pub use tree::Tree;
+
+#[derive(Debug, Clone, Copy)]
 pub struct Marker {
     pub visited: bool,
 }
@@ -27,6 +66,27 @@ impl Add<Pos> for Pos {
     }
 }
 
+impl Sub<Pos> for Pos {
+    type Output = Pos;
+    fn sub(self, rhs: Pos) -> Self::Output {
+        Pos { row: self.row - rhs.row, col: self.col - rhs.col }
+    }
+}
+
+impl Neg for Pos {
+    type Output = Pos;
+    fn neg(self) -> Self::Output {
+        Pos { row: -self.row, col: -self.col }
+    }
+}
+
+impl Mul<i32> for Pos {
+    type Output = Pos;
+    fn mul(self, rhs: i32) -> Self::Output {
+        Pos { row: self.row * rhs, col: self.col * rhs }
+    }
+}
+
 impl Display for Pos {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "(r{},c{})", self.row, self.col)
@@ -49,243 +109,241 @@ impl Pos {
     pub fn neighbours(&self) -> [Pos; 4] {
         [self.left(), self.top(), self.right(), self.bottom()]
     }
+    pub fn neighbours8(&self) -> [Pos; 8] {
+        Direction::ALL.map(|direction| self.step(direction, 1))
+    }
+    pub fn step(&self, direction: Direction, n: i32) -> Pos {
+        *self + direction.delta() * n
+    }
+    pub fn signum(&self) -> Pos {
+        Pos { row: self.row.signum(), col: self.col.signum() }
+    }
+    pub fn manhattan(&self, other: &Pos) -> i32 {
+        (self.row - other.row).abs() + (self.col - other.col).abs()
+    }
+    pub fn chebyshev(&self, other: &Pos) -> i32 {
+        i32::max((self.row - other.row).abs(), (self.col - other.col).abs())
+    }
+    pub fn euclidean_squared(&self, other: &Pos) -> i32 {
+        let delta = *self - *other;
+        delta.row * delta.row + delta.col * delta.col
+    }
 }
 
-#[derive(Debug)]
-pub struct Size {
-    pub min_row: i32,
-    pub max_row: i32,
-    pub min_col: i32,
-    pub max_col: i32,
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Direction {
+    Top,
+    TopRight,
+    Right,
+    BottomRight,
+    Bottom,
+    BottomLeft,
+    Left,
+    TopLeft,
 }
 
-pub fn map_size<T>(map: &HashMap<Pos, T>) -> Size {
-    Size {
-        min_row: map.keys().map(|pos| pos.row).min().expect("bad data"),
-        max_row: map.keys().map(|pos| pos.row).max().expect("bad data"),
-        min_col: map.keys().map(|pos| pos.col).min().expect("bad data"),
-        max_col: map.keys().map(|pos| pos.col).max().expect("bad data"),
+impl Direction {
+    //  Clockwise, starting from the top
+    pub const ALL: [Direction; 8] = [
+        Direction::Top,
+        Direction::TopRight,
+        Direction::Right,
+        Direction::BottomRight,
+        Direction::Bottom,
+        Direction::BottomLeft,
+        Direction::Left,
+        Direction::TopLeft,
+    ];
+    //  Same order as Pos::neighbours
+    pub const CARDINAL: [Direction; 4] = [Direction::Left, Direction::Top, Direction::Right, Direction::Bottom];
+    pub const DIAGONAL: [Direction; 4] = [Direction::TopLeft, Direction::TopRight, Direction::BottomRight, Direction::BottomLeft];
+
+    //  Accepts both "LURD" and "<^>v" notations
+    pub fn from_char(ch: char) -> Option<Direction> {
+        match ch {
+            'L' | '<' => Some(Direction::Left),
+            'U' | '^' => Some(Direction::Top),
+            'R' | '>' => Some(Direction::Right),
+            'D' | 'v' => Some(Direction::Bottom),
+            _ => None
+        }
     }
-}
 
-pub fn print_map<T>(map: &HashMap<Pos, T>) where T: Display {
-    let size = map_size(map);
-    for row in size.min_row..=size.max_row {
-        for col in size.min_col..=size.max_col {
-            let str = match map.get(&Pos { row, col }) {
-                None => String::from("."),
-                Some(state) => state.to_string()
-            };
-            print!("{} ", str);
+    pub fn delta(&self) -> Pos {
+        match self {
+            Direction::Top => Pos { row: -1, col: 0 },
+            Direction::TopRight => Pos { row: -1, col: 1 },
+            Direction::Right => Pos { row: 0, col: 1 },
+            Direction::BottomRight => Pos { row: 1, col: 1 },
+            Direction::Bottom => Pos { row: 1, col: 0 },
+            Direction::BottomLeft => Pos { row: 1, col: -1 },
+            Direction::Left => Pos { row: 0, col: -1 },
+            Direction::TopLeft => Pos { row: -1, col: -1 },
         }
-        println!(" {}", row);
     }
-    println!("---");
-}
-
-// ---
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct NodeId(String);
+    pub fn is_diagonal(&self) -> bool {
+        Direction::DIAGONAL.contains(self)
+    }
 
-impl Display for NodeId {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+    //  Rotates clockwise by 45 degree steps, negative values rotate counterclockwise
+    pub fn rotate(&self, eighths: i32) -> Direction {
+        let index = Direction::ALL.iter().position(|direction| direction == self).expect("illegal state");
+        Direction::ALL[(index as i32 + eighths).rem_euclid(8) as usize]
     }
-}
 
-impl NodeId {
-    pub fn from(s: &str) -> NodeId {
-        NodeId(String::from(s))
+    pub fn turn_left(&self) -> Direction {
+        self.rotate(-2)
     }
-    pub fn from_path(path: &Vec<NodeId>) -> NodeId {
-        NodeId(path
-            .iter()
-            .map(|n| n.0.as_str())
-            .collect::<Vec<&str>>()
-            .join("/")
-            .replacen("//", "/", 1))
+
+    pub fn turn_right(&self) -> Direction {
+        self.rotate(2)
     }
-    pub fn append(&self, node: &NodeId) -> NodeId {
-        let mut path = Vec::with_capacity(2);
-        path.push(self.clone());
-        path.push(node.clone());
-        NodeId::from_path(&path)
+
+    pub fn reverse(&self) -> Direction {
+        self.rotate(4)
     }
 }
 
-pub struct Graph<NA> where NA: Clone {
-    pub nodes: HashSet<NodeId>,
-    pub node_attributes: HashMap<NodeId, NA>,
-    pub edges_to: HashMap<NodeId, HashSet<NodeId>>,
-    pub edges_from: HashMap<NodeId, HashSet<NodeId>>,
+#[test]
+fn test_direction() {
+    let pos = Pos { row: 2, col: 3 };
+    assert_eq!(Pos { row: -1, col: 6 }, pos.step(Direction::TopRight, 3));
+    assert_eq!(Direction::Left, Direction::Top.turn_left());
+    assert_eq!(Direction::BottomLeft, Direction::TopRight.reverse());
+    assert_eq!(Direction::TopLeft, Direction::Top.rotate(-1));
+    assert_eq!(pos.neighbours().map(|n| n - pos), Direction::CARDINAL.map(|d| d.delta()));
+    assert_eq!(8, pos.neighbours8().iter().filter(|n| n.chebyshev(&pos) == 1).count());
+    assert_eq!(7, pos.manhattan(&Pos { row: -1, col: 7 }));
+    assert_eq!(4, pos.chebyshev(&Pos { row: -1, col: 7 }));
+    assert_eq!(25, pos.euclidean_squared(&Pos { row: -1, col: 7 }));
+    assert_eq!(Pos { row: -1, col: 1 }, (-(pos * 2) + Pos { row: 0, col: 9 }).signum());
 }
 
-impl<NA> Graph<NA> where NA: Clone {
-    pub fn get_incoming(&self, node: &NodeId) -> Option<&HashSet<NodeId>> {
-        self.edges_to.get(node)
-    }
+pub fn print_map<T>(map: &HashMap<Pos, T>) where T: Display {
+    let renderer = MapRenderer::new()
+        .separator(" ")
+        .row_labels(true)
+        .footer("---");
+    print!("{}", renderer.render_to_string(map));
+}
 
-    pub fn add_node(&self, node: &NodeId, attr: Option<NA>) -> Graph<NA> {
-        Graph {
-            nodes: {
-                let mut set = self.nodes.clone();
-                set.insert(node.clone());
-                set
-            },
-            node_attributes: {
-                match attr {
-                    None => self.node_attributes.clone(),
-                    Some(attr) => {
-                        let mut map = self.node_attributes.clone();
-                        map.insert(node.clone(), attr);
-                        map
-                    }
-                }
-            },
-            edges_to: self.edges_to.clone(),
-            edges_from: self.edges_from.clone(),
-        }
-    }
+// ---
 
-    pub fn add_edge(&self, from: &NodeId, to: &NodeId) -> Graph<NA> {
-        assert!(self.nodes.contains(from));
-        assert!(self.nodes.contains(to));
-
-        Graph {
-            nodes: self.nodes.clone(),
-            node_attributes: self.node_attributes.clone(),
-            edges_to: {
-                let mut map = self.edges_to.clone();
-                if !map.contains_key(to) {
-                    map.insert((*to).clone(), HashSet::new());
-                }
-                map.get_mut(to).expect("illegal state").insert((*from).clone());
-                map
-            },
-            edges_from: {
-                let mut map = self.edges_from.clone();
-                if !map.contains_key(from) {
-                    map.insert((*from).clone(), HashSet::new());
-                }
-                map.get_mut(from).expect("illegal state").insert((*to).clone());
-                map
-            },
-        }
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(String);
+
+impl Display for NodeId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
     }
+}
 
-    pub fn new() -> Graph<NA> {
-        Graph {
-            nodes: HashSet::new(),
-            node_attributes: HashMap::new(),
-            edges_to: HashMap::new(),
-            edges_from: HashMap::new(),
-        }
+impl NodeId {
+    pub fn from(s: &str) -> NodeId {
+        NodeId(String::from(s))
     }
 }
 
-pub fn print_graph_dot<NA>(graph: &Graph<NA>) where NA: Display, NA: Clone {
+pub fn print_graph_dot<NA, EA, K>(graph: &Graph<NA, EA, K>) where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
     let buf = build_graph_dot(graph, |_| None);
     println!("{}", buf);
 }
 
-pub fn print_graph_dot2<NA>(graph: &Graph<NA>, node_decorator: fn(&NA) -> Option<&str>) where NA: Display, NA: Clone {
+pub fn print_graph_dot2<NA, EA, K>(graph: &Graph<NA, EA, K>, node_decorator: fn(&NA) -> Option<&str>) where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
     let buf = build_graph_dot(graph, node_decorator);
     println!("{}", buf);
 }
 
-pub fn create_dot_file<NA>(graph: &Graph<NA>, filename: &str) where NA: Display, NA: Clone {
+pub fn create_dot_file<NA, EA, K>(graph: &Graph<NA, EA, K>, filename: &str) where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
     create_dot_file2(graph, |_| None, filename);
 }
 
-pub fn create_dot_file2<NA>(graph: &Graph<NA>, node_decorator: fn(&NA) -> Option<&str>, filename: &str) where NA: Display, NA: Clone {
+pub fn create_dot_file2<NA, EA, K>(graph: &Graph<NA, EA, K>, node_decorator: fn(&NA) -> Option<&str>, filename: &str) where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
     let buf = build_graph_dot(graph, node_decorator);
     let mut file = File::create(filename).unwrap();
     write!(file, "{}", buf).unwrap();
 }
 
-fn build_graph_dot<NA>(graph: &Graph<NA>, node_decorator: fn(&NA) -> Option<&str>) -> String where NA: Display, NA: Clone {
-    let mut buf = String::new();
-    writeln!(buf, "digraph G {{").unwrap();
-    for node_id in graph.nodes.iter() {
-        let (attr, decoration) = match graph.node_attributes.get(node_id) {
-            Some(state) => (state.to_string(), node_decorator(state).unwrap_or("")),
-            None => (String::from(""), "")
-        };
-        write!(buf, "  {0} [label=\"{0}, {1}\"{2}]\n", node_id, attr, decoration).unwrap();
-        if let Some(to_nodes) = graph.edges_from.get(node_id) {
-            if !to_nodes.is_empty() {
-                write!(buf, "  {} -> {{", node_id).unwrap();
-                write_node_ids(&mut buf, to_nodes);
-                writeln!(buf, "}}").unwrap();
-            }
-        }
-    }
-    write!(buf, "}}").unwrap();
-    buf
+fn build_graph_dot<NA, EA, K>(graph: &Graph<NA, EA, K>, node_decorator: fn(&NA) -> Option<&str>) -> String where NA: Display + Clone, EA: Weight, K: Display + Clone + Eq + Hash {
+    DotRenderer::new().node_decorator(node_decorator).render(graph)
 }
 
-fn write_node_ids(buf: &mut String, nodes: &HashSet<NodeId>) {
-    let mut first = true;
-    for node_id in nodes.iter() {
-        if first {
-            first = false;
-        } else if nodes.len() > 1 {
-            write!(buf, "; ").unwrap();
-        }
-        write!(buf, "{}", node_id).unwrap();
-    }
-}
-
-pub type Dist = HashMap<NodeId, u32>;
-pub type Prev = HashMap<NodeId, NodeId>;
-
-pub fn dijkstra<NA>(graph: &Graph<NA>, start: &NodeId) -> (Dist, Prev) where NA: Clone
-{
-    let mut dist = HashMap::new();
-    let mut prev = HashMap::new();
-    let mut queue = Vec::new();
+pub type Dist<K = NodeId> = HashMap<K, u32>;
+pub type Prev<K = NodeId> = HashMap<K, K>;
 
-    for node_id in graph.nodes.iter() {
-        dist.insert(node_id.clone(), u32::MAX);
-        queue.push(node_id.clone());
-    }
-
-    dist.insert(start.clone(), 0);
-
-    while !queue.is_empty() {
-        let min_index = queue.iter()
-            .enumerate()
-            .min_by(|(_, a), (_, b)| dist[a].cmp(&dist[b]))
-            .map(|(index, _)| index)
-            .unwrap();
-
-        let u = queue.remove(min_index);
-
-        if let Some(edges_to) = graph.edges_to.get(&u) {
-            for d in edges_to.iter() {
-                if !queue.contains(&d) { continue; }
-                let du = dist[&u];
-                if du == u32::MAX {
-                    continue;
-                }
-                let alt = du + 1;
-                if alt < dist[&d] {
-                    dist.insert(d.clone(), alt);
-                    prev.insert(d.clone(), u.clone());
-                }
-            }
-        }
-    }
+//  Distances from `start`, following edges in their direction
+pub fn dijkstra<NA, EA, K>(graph: &Graph<NA, EA, K>, start: &K) -> (Dist<K>, Prev<K>) where NA: Clone, EA: Weight, K: Clone + Eq + Hash {
+    shortest_paths(graph, start, None)
+}
 
+//  Unreachable nodes are absent from `Dist`, as are nodes whose distance wouldn't fit in a u32.
+//  With a `target` the search stops as soon as the target is settled, distances of other nodes
+//  may then be upper bounds only
+pub fn shortest_paths<NA, EA, K>(graph: &Graph<NA, EA, K>, start: &K, target: Option<&K>) -> (Dist<K>, Prev<K>) where NA: Clone, EA: Weight, K: Clone + Eq + Hash {
+    let Some(start) = graph.index_of(start) else {
+        return (HashMap::new(), HashMap::new());
+    };
+    let target = target.and_then(|target| graph.index_of(target));
+
+    let explored = search::explore([start], |u| Some(*u) == target, |u| graph.weighted_successors_at(*u), |_| 0);
+    let dist = explored.states.iter()
+        .zip(&explored.cost)
+        .map(|(u, d)| (graph.key(*u).clone(), *d))
+        .collect();
+    let prev = explored.states.iter()
+        .zip(&explored.prev)
+        .filter_map(|(v, u)| Some((graph.key(*v).clone(), graph.key(explored.states[(*u)?]).clone())))
+        .collect();
     (dist, prev)
 }
 
-pub fn get_path(prev: &Prev, from: &NodeId) -> Vec<NodeId> {
+//  Nodes from `from` back to the start of the search, see `PathResult` for start-to-goal order
+pub fn get_path<K>(prev: &Prev<K>, from: &K) -> Vec<K> where K: Clone + Eq + Hash {
     let mut path = Vec::new();
     path.push(from.clone());
-    while prev.contains_key(&path.last().unwrap()) {
+    while prev.contains_key(path.last().unwrap()) {
         path.push(prev[path.last().unwrap()].clone());
     }
     path
 }
+
+#[test]
+fn test_shortest_paths() {
+    let nodes = ["a", "b", "c", "d", "e"].map(NodeId::from);
+    let [a, b, c, d, e] = nodes.clone();
+    let mut graph: Graph<()> = Graph::new();
+    for node in &nodes {
+        graph.insert_node(node, None);
+    }
+    graph.insert_weighted_edge(&a, &b, 7);
+    graph.insert_weighted_edge(&a, &c, 2);
+    graph.insert_weighted_edge(&c, &b, 3);
+    graph.insert_edge(&b, &d);
+    graph.insert_edge(&d, &a);
+
+    let (dist, prev) = dijkstra(&graph, &a);
+    assert_eq!(Some(&5), dist.get(&b));
+    assert_eq!(Some(&6), dist.get(&d));
+    assert_eq!(None, dist.get(&e));
+    assert_eq!(vec![d.clone(), b.clone(), c.clone(), a.clone()], get_path(&prev, &d));
+
+    let (dist, _) = shortest_paths(&graph, &a, Some(&c));
+    assert_eq!(Some(&2), dist.get(&c));
+    assert_eq!(None, dist.get(&d));
+
+    //  Only `d -> a` leads back to the start, so `a` is one step from `d` but not the other way round
+    let (dist, prev) = dijkstra(&graph, &d);
+    assert_eq!(Some(&1), dist.get(&a));
+    assert_eq!(Some(&6), dist.get(&b));
+    assert_eq!(Some(&d), prev.get(&a));
+
+    let mut far = graph.clone();
+    far.insert_weighted_edge(&d, &e, u32::MAX);
+    assert_eq!(None, dijkstra(&far, &a).0.get(&e));
+    assert_eq!(Some(&u32::MAX), dijkstra(&far, &d).0.get(&e));
+
+    graph.remove_edge(&c, &b);
+    assert_eq!(None, graph.edge_weight(&c, &b));
+    assert_eq!(Some(&8), dijkstra(&graph, &a).0.get(&d));
+}
@@ -0,0 +1,94 @@
+use std::fmt::{Display, Formatter};
+
+//  Absolute path like `/a/b` made of its components, the root has none.
+//  Orders parents before their children and siblings by name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodePath(Vec<String>);
+
+impl NodePath {
+    pub fn root() -> NodePath {
+        NodePath(Vec::new())
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    //  Number of components, 0 for the root
+    pub fn depth(&self) -> usize {
+        self.0.len()
+    }
+
+    //  None for the root
+    pub fn parent(&self) -> Option<NodePath> {
+        let (_, parent) = self.0.split_last()?;
+        Some(NodePath(parent.to_vec()))
+    }
+
+    pub fn components(&self) -> impl Iterator<Item=&str> + '_ {
+        self.0.iter().map(String::as_str)
+    }
+
+    //  Last component, None for the root
+    pub fn file_name(&self) -> Option<&str> {
+        self.0.last().map(String::as_str)
+    }
+
+    //  `name` may have several components like `a/b`, empty ones are skipped
+    pub fn join(&self, name: &str) -> NodePath {
+        let mut path = self.clone();
+        path.0.extend(name.split('/').filter(|c| !c.is_empty()).map(String::from));
+        path
+    }
+
+    //  Whether `prefix` is this path or one of its ancestors
+    pub fn starts_with(&self, prefix: &NodePath) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+}
+
+impl From<&str> for NodePath {
+    fn from(s: &str) -> Self {
+        NodePath::root().join(s)
+    }
+}
+
+impl Display for NodePath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_root() {
+            return write!(f, "/");
+        }
+        for component in &self.0 {
+            write!(f, "/{}", component)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_node_path() {
+    let root = NodePath::root();
+    let file = root.join("a").join("b.txt");
+    assert_eq!(NodePath::from("/a/b.txt"), file);
+    assert_eq!(NodePath::from("a//b.txt/"), file);
+    assert_eq!("/a/b.txt", file.to_string());
+    assert_eq!("/", root.to_string());
+    assert_eq!(root, NodePath::from("/"));
+
+    assert_eq!(Some(NodePath::from("/a")), file.parent());
+    assert_eq!(Some(root.clone()), NodePath::from("/a").parent());
+    assert_eq!(None, root.parent());
+    assert_eq!(vec!["a", "b.txt"], file.components().collect::<Vec<_>>());
+    assert_eq!(Some("b.txt"), file.file_name());
+    assert_eq!(None, root.file_name());
+    assert_eq!(2, file.depth());
+
+    assert!(file.starts_with(&root));
+    assert!(file.starts_with(&NodePath::from("/a")));
+    assert!(file.starts_with(&file));
+    assert!(!NodePath::from("/ab").starts_with(&NodePath::from("/a")));
+
+    let mut paths = vec![NodePath::from("/b"), file.clone(), NodePath::from("/a"), root.clone(), NodePath::from("/a/a")];
+    paths.sort();
+    assert_eq!(vec![root, NodePath::from("/a"), NodePath::from("/a/a"), file, NodePath::from("/b")], paths);
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::{Grid, Pos};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridParseError {
+    pub line: usize,
+    pub col: usize,
+    pub message: String,
+}
+
+impl Display for GridParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.col, self.message)
+    }
+}
+
+impl Error for GridParseError {}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Markers(HashMap<char, Vec<Pos>>);
+
+impl Markers {
+    pub fn first(&self, marker: char) -> Option<Pos> {
+        self.all(marker).first().copied()
+    }
+
+    pub fn all(&self, marker: char) -> &[Pos] {
+        self.0.get(&marker).map(|positions| positions.as_slice()).unwrap_or(&[])
+    }
+}
+
+//  Cells are mapped with `f`, positions of `markers` are collected in reading order.
+//  Blank lines around the block and indentation shared by all lines are ignored,
+//  so inline fixtures can be written as indented multi-line string literals.
+pub fn parse_grid<T, F>(s: &str, markers: &[char], mut f: F) -> Result<(Grid<T>, Markers), GridParseError>
+    where F: FnMut(char) -> Option<T> {
+    let lines: Vec<(usize, &str)> = s.lines().enumerate().collect();
+    let first = lines.iter().position(|(_, line)| !line.trim().is_empty());
+    let last = lines.iter().rposition(|(_, line)| !line.trim().is_empty());
+    let lines = match (first, last) {
+        (Some(first), Some(last)) => &lines[first..=last],
+        _ => return Err(GridParseError { line: 1, col: 1, message: String::from("empty grid") })
+    };
+
+    let indent = lines.iter()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(_, line)| line.len() - line.trim_start_matches(' ').len())
+        .min()
+        .unwrap_or(0);
+
+    let mut found = Markers::default();
+    let mut rows = Vec::with_capacity(lines.len());
+    for (row, (line_index, line)) in lines.iter().enumerate() {
+        let line = line.get(indent..).unwrap_or("");
+        let mut cells = Vec::with_capacity(line.len());
+        for (col, ch) in line.chars().enumerate() {
+            if markers.contains(&ch) {
+                found.0.entry(ch).or_default().push(Pos { row: row as i32, col: col as i32 });
+            }
+            match f(ch) {
+                Some(cell) => cells.push(cell),
+                None => return Err(GridParseError {
+                    line: line_index + 1,
+                    col: indent + col + 1,
+                    message: format!("unexpected character '{}'", ch),
+                })
+            }
+        }
+        if let Some(expected) = rows.first().map(|first: &Vec<T>| first.len()) {
+            if cells.len() != expected {
+                return Err(GridParseError {
+                    line: line_index + 1,
+                    col: indent + cells.len().min(expected) + 1,
+                    message: format!("expected {} columns, found {}", expected, cells.len()),
+                });
+            }
+        } else if cells.is_empty() {
+            return Err(GridParseError { line: line_index + 1, col: 1, message: String::from("empty row") });
+        }
+        rows.push(cells);
+    }
+
+    Ok((Grid::from_rows(rows), found))
+}
+
+#[test]
+fn test_parse_grid() {
+    let (grid, markers) = parse_grid("
+        Sab
+        cdE
+    ", &['S', 'E'], |ch| match ch {
+        'S' => Some('a'),
+        'E' => Some('z'),
+        'a'..='z' => Some(ch),
+        _ => None
+    }).unwrap();
+
+    assert_eq!(3, grid.width());
+    assert_eq!(2, grid.height());
+    assert_eq!('a', grid[Pos { row: 0, col: 0 }]);
+    assert_eq!('z', grid[Pos { row: 1, col: 2 }]);
+    assert_eq!(Some(Pos { row: 0, col: 0 }), markers.first('S'));
+    assert_eq!(&[Pos { row: 1, col: 2 }], markers.all('E'));
+
+    let error = parse_grid("ab\nc?\n", &[], |ch| ch.is_alphabetic().then_some(ch)).unwrap_err();
+    assert_eq!(GridParseError { line: 2, col: 2, message: String::from("unexpected character '?'") }, error);
+
+    let error = parse_grid("ab\nabc\n", &[], Some).unwrap_err();
+    assert_eq!("line 2, column 3: expected 2 columns, found 3", error.to_string());
+}
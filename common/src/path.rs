@@ -0,0 +1,127 @@
+use std::hash::Hash;
+use crate::{dijkstra, Dist, Graph, NodeId, Prev, SearchPath, Weight};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathResult<K = NodeId> {
+    //  Nodes from start to goal, both included
+    Found { nodes: Vec<K>, cost: u32 },
+    Unreachable,
+}
+
+impl<K> PathResult<K> where K: Clone + Eq + Hash {
+    //  Follows `prev` back from the goal, `dist` and `prev` as returned by `dijkstra`
+    pub fn from_prev(dist: &Dist<K>, prev: &Prev<K>, start: &K, goal: &K) -> PathResult<K> {
+        let Some(cost) = dist.get(goal) else {
+            return PathResult::Unreachable;
+        };
+        let mut nodes = vec![goal.clone()];
+        while nodes.last() != Some(start) {
+            match prev.get(nodes.last().expect("illegal state")) {
+                Some(node) => nodes.push(node.clone()),
+                None => return PathResult::Unreachable,
+            }
+        }
+        nodes.reverse();
+        PathResult::Found { nodes, cost: *cost }
+    }
+
+    pub fn is_found(&self) -> bool {
+        matches!(self, PathResult::Found { .. })
+    }
+
+    pub fn nodes(&self) -> Option<&[K]> {
+        match self {
+            PathResult::Found { nodes, .. } => Some(nodes),
+            PathResult::Unreachable => None,
+        }
+    }
+
+    pub fn cost(&self) -> Option<u32> {
+        match self {
+            PathResult::Found { cost, .. } => Some(*cost),
+            PathResult::Unreachable => None,
+        }
+    }
+}
+
+impl<K> From<Option<SearchPath<K>>> for PathResult<K> {
+    fn from(found: Option<SearchPath<K>>) -> Self {
+        match found {
+            Some(SearchPath { path, cost }) => PathResult::Found { nodes: path, cost },
+            None => PathResult::Unreachable,
+        }
+    }
+}
+
+pub fn shortest_path<NA, EA, K>(graph: &Graph<NA, EA, K>, start: &K, goal: &K) -> PathResult<K> where NA: Clone, EA: Weight, K: Clone + Eq + Hash {
+    let (dist, prev) = dijkstra(graph, start);
+    PathResult::from_prev(&dist, &prev, start, goal)
+}
+
+//  Every path of minimal cost when there are ties, in the order of the graph's edges, none when
+//  the goal is unreachable. Their number can grow exponentially with the number of ties along the way
+pub fn all_shortest_paths<NA, EA, K>(graph: &Graph<NA, EA, K>, start: &K, goal: &K) -> Vec<SearchPath<K>> where NA: Clone, EA: Weight, K: Clone + Eq + Hash {
+    let (dist, _) = dijkstra(graph, start);
+    let Some(cost) = dist.get(goal).copied() else {
+        return Vec::new();
+    };
+
+    //  Walks back from the goal over edges that keep the distance tight,
+    //  skipping nodes already on the path in case of zero weight cycles
+    let mut paths = Vec::new();
+    let mut stack = vec![vec![goal.clone()]];
+    while let Some(nodes) = stack.pop() {
+        let node = nodes.last().expect("illegal state");
+        if node == start {
+            let mut path = nodes;
+            path.reverse();
+            paths.push(SearchPath { path, cost });
+            continue;
+        }
+        let mut predecessors = graph.predecessors(node)
+            .filter(|from| !nodes.contains(from))
+            .filter(|from| match (dist.get(*from), graph.edge_weight(from, node)) {
+                (Some(d), Some(weight)) => d.checked_add(weight) == Some(dist[node]),
+                _ => false,
+            })
+            .collect::<Vec<_>>();
+        predecessors.reverse();
+        for from in predecessors {
+            let mut nodes = nodes.clone();
+            nodes.push(from.clone());
+            stack.push(nodes);
+        }
+    }
+    paths
+}
+
+#[test]
+fn test_path_result() {
+    let nodes = ["a", "b", "c", "d", "e"].map(NodeId::from);
+    let [a, b, c, d, e] = nodes.clone();
+    let mut graph: Graph<()> = Graph::new();
+    for node in &nodes {
+        graph.insert_node(node, None);
+    }
+    graph.insert_weighted_edge(&a, &b, 2);
+    graph.insert_weighted_edge(&a, &c, 1);
+    graph.insert_weighted_edge(&c, &b, 1);
+    graph.insert_weighted_edge(&b, &d, 1);
+    graph.insert_weighted_edge(&c, &d, 2);
+
+    let found = shortest_path(&graph, &a, &d);
+    assert_eq!(Some(3), found.cost());
+    assert_eq!(Some(2), shortest_path(&graph, &a, &b).cost());
+    assert_eq!(PathResult::Unreachable, shortest_path(&graph, &a, &e));
+    assert_eq!(PathResult::Unreachable, shortest_path(&graph, &d, &a));
+    assert_eq!(Some(&[a.clone()][..]), shortest_path(&graph, &a, &a).nodes());
+
+    let all = all_shortest_paths(&graph, &a, &d);
+    assert_eq!(vec![
+        SearchPath { path: vec![a.clone(), b.clone(), d.clone()], cost: 3 },
+        SearchPath { path: vec![a.clone(), c.clone(), b.clone(), d.clone()], cost: 3 },
+        SearchPath { path: vec![a.clone(), c.clone(), d.clone()], cost: 3 },
+    ], all);
+    assert!(all.into_iter().map(|path| PathResult::from(Some(path))).any(|path| path == found));
+    assert!(all_shortest_paths(&graph, &a, &e).is_empty());
+}
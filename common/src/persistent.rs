@@ -0,0 +1,274 @@
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash};
+use std::ops::{Index, IndexMut};
+use std::sync::Arc;
+
+//  Persistent collections behind Graph. Cloning only copies the root pointer, updates go through
+//  `Arc::make_mut` and so copy just the nodes on the path that are still shared with a clone
+
+const BITS: u32 = 5;
+const MASK: u64 = (1 << BITS) - 1;
+
+#[derive(Clone)]
+enum VecNode<T> {
+    Leaf(Vec<T>),
+    Branch(Vec<Arc<VecNode<T>>>),
+}
+
+//  Radix tree with 32 slots per node, so indexing and updates take O(log32 n) steps
+#[derive(Clone)]
+pub(crate) struct PVec<T> {
+    root: Arc<VecNode<T>>,
+    shift: u32,
+    len: usize,
+}
+
+impl<T> PVec<T> where T: Clone {
+    pub fn new() -> PVec<T> {
+        PVec { root: Arc::new(VecNode::Leaf(Vec::new())), shift: 0, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        let mut node = &*self.root;
+        let mut shift = self.shift;
+        loop {
+            match node {
+                VecNode::Leaf(items) => return items.get(index & MASK as usize),
+                VecNode::Branch(children) => {
+                    node = &children[(index >> shift) & MASK as usize];
+                    shift -= BITS;
+                }
+            }
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        let mut node = Arc::make_mut(&mut self.root);
+        let mut shift = self.shift;
+        loop {
+            match node {
+                VecNode::Leaf(items) => return items.get_mut(index & MASK as usize),
+                VecNode::Branch(children) => {
+                    node = Arc::make_mut(&mut children[(index >> shift) & MASK as usize]);
+                    shift -= BITS;
+                }
+            }
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        //  Full tree, grow a level on top
+        if self.len == 1 << (self.shift + BITS) {
+            let root = std::mem::replace(&mut self.root, Arc::new(VecNode::Branch(Vec::new())));
+            self.root = Arc::new(VecNode::Branch(vec![root]));
+            self.shift += BITS;
+        }
+        let index = self.len;
+        let mut node = Arc::make_mut(&mut self.root);
+        let mut shift = self.shift;
+        loop {
+            match node {
+                VecNode::Leaf(items) => {
+                    items.push(value);
+                    break;
+                }
+                VecNode::Branch(children) => {
+                    let slot = (index >> shift) & MASK as usize;
+                    if slot == children.len() {
+                        let child = if shift == BITS { VecNode::Leaf(Vec::new()) } else { VecNode::Branch(Vec::new()) };
+                        children.push(Arc::new(child));
+                    }
+                    node = Arc::make_mut(&mut children[slot]);
+                    shift -= BITS;
+                }
+            }
+        }
+        self.len += 1;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&T> + '_ {
+        (0..self.len).map(|i| &self[i])
+    }
+}
+
+impl<T> Index<usize> for PVec<T> where T: Clone {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> IndexMut<usize> for PVec<T> where T: Clone {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T> Debug for PVec<T> where T: Clone + Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[derive(Clone)]
+enum MapNode<K, V> {
+    //  Children for the set bits of `bitmap`, in bit order
+    Branch { bitmap: u32, children: Vec<Arc<MapNode<K, V>>> },
+    //  All keys with the same full hash
+    Leaf { hash: u64, entries: Vec<(K, V)> },
+}
+
+//  Hash array mapped trie, each level consumes 5 bits of the key's hash.
+//  Keys are never removed, which is all Graph needs
+#[derive(Clone)]
+pub(crate) struct PMap<K, V> {
+    root: Arc<MapNode<K, V>>,
+}
+
+fn hash_of<K>(key: &K) -> u64 where K: Hash {
+    BuildHasherDefault::<DefaultHasher>::default().hash_one(key)
+}
+
+fn bit_at(hash: u64, shift: u32) -> u32 {
+    1 << ((hash >> shift) & MASK)
+}
+
+impl<K, V> PMap<K, V> where K: Clone + Eq + Hash, V: Clone {
+    pub fn new() -> PMap<K, V> {
+        PMap { root: Arc::new(MapNode::Branch { bitmap: 0, children: Vec::new() }) }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let hash = hash_of(key);
+        let mut node = &*self.root;
+        let mut shift = 0;
+        loop {
+            match node {
+                MapNode::Branch { bitmap, children } => {
+                    let bit = bit_at(hash, shift);
+                    if bitmap & bit == 0 {
+                        return None;
+                    }
+                    node = &children[(bitmap & (bit - 1)).count_ones() as usize];
+                    shift += BITS;
+                }
+                MapNode::Leaf { hash: leaf_hash, entries } => {
+                    if *leaf_hash != hash {
+                        return None;
+                    }
+                    return entries.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+                }
+            }
+        }
+    }
+
+    //  Returns the previous value of the key
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let hash = hash_of(&key);
+        Self::insert_at(Arc::make_mut(&mut self.root), 0, hash, key, value)
+    }
+
+    fn insert_at(node: &mut MapNode<K, V>, shift: u32, hash: u64, key: K, value: V) -> Option<V> {
+        match node {
+            MapNode::Branch { bitmap, children } => {
+                let bit = bit_at(hash, shift);
+                let position = (*bitmap & (bit - 1)).count_ones() as usize;
+                if *bitmap & bit == 0 {
+                    *bitmap |= bit;
+                    children.insert(position, Arc::new(MapNode::Leaf { hash, entries: vec![(key, value)] }));
+                    return None;
+                }
+                let child = Arc::make_mut(&mut children[position]);
+                if let MapNode::Leaf { hash: leaf_hash, .. } = child {
+                    //  Different hashes diverge within 64 bits, push the leaf one level down
+                    if *leaf_hash != hash {
+                        let bitmap = bit_at(*leaf_hash, shift + BITS);
+                        let leaf = std::mem::replace(child, MapNode::Branch { bitmap, children: Vec::new() });
+                        if let MapNode::Branch { children, .. } = child {
+                            children.push(Arc::new(leaf));
+                        }
+                    }
+                }
+                Self::insert_at(child, shift + BITS, hash, key, value)
+            }
+            MapNode::Leaf { entries, .. } => {
+                match entries.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, v)) => Some(std::mem::replace(v, value)),
+                    None => {
+                        entries.push((key, value));
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(&K, &V)> + '_ {
+        let mut stack = vec![&*self.root];
+        let mut entries: Vec<(&K, &V)> = Vec::new();
+        while let Some(node) = stack.pop() {
+            match node {
+                MapNode::Branch { children, .. } => stack.extend(children.iter().map(|child| &**child)),
+                MapNode::Leaf { entries: leaf, .. } => entries.extend(leaf.iter().map(|(k, v)| (k, v))),
+            }
+        }
+        entries.into_iter()
+    }
+}
+
+impl<K, V> Debug for PMap<K, V> where K: Clone + Eq + Hash + Debug, V: Clone + Debug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[test]
+fn test_persistent_vec() {
+    let mut v = PVec::new();
+    for i in 0..2000 {
+        v.push(i);
+    }
+    let snapshot = v.clone();
+    v[1500] = -1;
+    v.push(2000);
+
+    assert_eq!(2001, v.len());
+    assert_eq!(2000, snapshot.len());
+    assert_eq!(-1, v[1500]);
+    assert_eq!(1500, snapshot[1500]);
+    assert_eq!(Some(&2000), v.get(2000));
+    assert_eq!(None, snapshot.get(2000));
+    assert!(snapshot.iter().copied().eq(0..2000));
+    //  Only the path to the updated leaf was copied
+    assert!(v.get(0).is_some_and(|first| std::ptr::eq(first, &snapshot[0])));
+    assert!(!std::ptr::eq(&v[1499], &snapshot[1499]));
+}
+
+#[test]
+fn test_persistent_map() {
+    let mut m = PMap::new();
+    for i in 0..2000 {
+        assert_eq!(None, m.insert(i, i * 2));
+    }
+    let snapshot = m.clone();
+    assert_eq!(Some(3000), m.insert(1500, 0));
+    m.insert(5000, 1);
+
+    assert_eq!(2000, snapshot.iter().count());
+    assert_eq!(Some(&0), m.get(&1500));
+    assert_eq!(Some(&3000), snapshot.get(&1500));
+    assert_eq!(None, snapshot.get(&5000));
+    assert!((0..2000).all(|i| snapshot.get(&i) == Some(&(i * 2))));
+    assert_eq!(2001, m.iter().count());
+}
@@ -0,0 +1,231 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub};
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Point<const N: usize>(pub [i32; N]);
+
+pub type Point2 = Point<2>;
+pub type Point3 = Point<3>;
+
+impl<const N: usize> Point<N> {
+    pub fn origin() -> Point<N> {
+        Point([0; N])
+    }
+
+    pub fn plus_axis(&self, axis: usize, delta: i32) -> Point<N> {
+        let mut point = *self;
+        point.0[axis] += delta;
+        point
+    }
+
+    //  2N points at `delta` along each axis, ordered by axis, negative side first
+    pub fn neighbours(&self, delta: i32) -> Vec<Point<N>> {
+        (0..N)
+            .flat_map(|axis| [self.plus_axis(axis, -delta), self.plus_axis(axis, delta)])
+            .collect()
+    }
+
+    //  All 3^N - 1 points of the surrounding cube, diagonals included
+    pub fn neighbours_all(&self, delta: i32) -> Vec<Point<N>> {
+        let cube = Bounds { min: Point([-delta; N]), max: Point([delta; N]) };
+        cube.iter_step(delta)
+            .filter(|offset| *offset != Point::origin())
+            .map(|offset| *self + offset)
+            .collect()
+    }
+
+    pub fn manhattan(&self, other: &Point<N>) -> i32 {
+        (0..N).map(|axis| (self.0[axis] - other.0[axis]).abs()).sum()
+    }
+}
+
+impl<const N: usize> Display for Point<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(")?;
+        for (axis, value) in self.0.iter().enumerate() {
+            if axis > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", value)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+    type Output = i32;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Point<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.0[axis]
+    }
+}
+
+impl<const N: usize> Add for Point<N> {
+    type Output = Point<N>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point(std::array::from_fn(|axis| self.0[axis] + rhs.0[axis]))
+    }
+}
+
+impl<const N: usize> Sub for Point<N> {
+    type Output = Point<N>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point(std::array::from_fn(|axis| self.0[axis] - rhs.0[axis]))
+    }
+}
+
+impl<const N: usize> Neg for Point<N> {
+    type Output = Point<N>;
+
+    fn neg(self) -> Self::Output {
+        Point(self.0.map(|value| -value))
+    }
+}
+
+impl<const N: usize> Mul<i32> for Point<N> {
+    type Output = Point<N>;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Point(self.0.map(|value| value * rhs))
+    }
+}
+
+impl<const N: usize> Div<i32> for Point<N> {
+    type Output = Point<N>;
+
+    fn div(self, rhs: i32) -> Self::Output {
+        //  Only allow integer division without remainders
+        assert!(self.0.iter().all(|value| value.rem_euclid(rhs) == 0));
+        Point(self.0.map(|value| value / rhs))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePointError(String);
+
+impl Display for ParsePointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "bad point \"{}\"", self.0)
+    }
+}
+
+impl Error for ParsePointError {}
+
+//  Parses comma separated coordinates like "1,2,3"
+impl<const N: usize> FromStr for Point<N> {
+    type Err = ParsePointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s.split(',')
+            .map(|value| value.trim().parse::<i32>())
+            .collect::<Result<Vec<i32>, _>>()
+            .map_err(|_| ParsePointError(String::from(s)))?;
+        let values: [i32; N] = values.try_into().map_err(|_| ParsePointError(String::from(s)))?;
+        Ok(Point(values))
+    }
+}
+
+pub fn parse_points<const N: usize>(s: &str) -> Result<Vec<Point<N>>, ParsePointError> {
+    s.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.parse())
+        .collect()
+}
+
+//  Inclusive axis-aligned box
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Bounds<const N: usize> {
+    pub min: Point<N>,
+    pub max: Point<N>,
+}
+
+impl<const N: usize> Bounds<N> {
+    pub fn from_points<I>(points: I) -> Option<Bounds<N>> where I: IntoIterator<Item=Point<N>> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Bounds { min: first, max: first };
+        for point in points {
+            bounds.include(&point);
+        }
+        Some(bounds)
+    }
+
+    pub fn include(&mut self, point: &Point<N>) {
+        for axis in 0..N {
+            self.min.0[axis] = self.min.0[axis].min(point.0[axis]);
+            self.max.0[axis] = self.max.0[axis].max(point.0[axis]);
+        }
+    }
+
+    pub fn contains(&self, point: &Point<N>) -> bool {
+        (0..N).all(|axis| self.min.0[axis] <= point.0[axis] && point.0[axis] <= self.max.0[axis])
+    }
+
+    pub fn expand(&self, delta: i32) -> Bounds<N> {
+        Bounds { min: Point(self.min.0.map(|v| v - delta)), max: Point(self.max.0.map(|v| v + delta)) }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=Point<N>> {
+        self.iter_step(1)
+    }
+
+    //  Points from `min` in increments of `step` along every axis, the last axis changes fastest
+    pub fn iter_step(&self, step: i32) -> impl Iterator<Item=Point<N>> {
+        assert!(step > 0);
+        let bounds = *self;
+        let mut next = if N > 0 && (0..N).all(|axis| bounds.min.0[axis] <= bounds.max.0[axis]) {
+            Some(bounds.min)
+        } else {
+            None
+        };
+        std::iter::from_fn(move || {
+            let current = next?;
+            let mut point = current;
+            next = None;
+            for axis in (0..N).rev() {
+                point.0[axis] += step;
+                if point.0[axis] <= bounds.max.0[axis] {
+                    next = Some(point);
+                    break;
+                }
+                point.0[axis] = bounds.min.0[axis];
+            }
+            Some(current)
+        })
+    }
+}
+
+#[test]
+fn test_point() {
+    let point: Point3 = "1, 2,3".parse().unwrap();
+    assert_eq!(Point([1, 2, 3]), point);
+    assert_eq!("(1,2,3)", point.to_string());
+    assert!("1,2".parse::<Point3>().is_err());
+    assert_eq!(Point([2, 4, 6]), point * 4 / 2);
+    assert_eq!(Point([0, 0, 0]), point - point);
+    assert_eq!(vec![
+        Point([0, 2, 3]), Point([2, 2, 3]),
+        Point([1, 1, 3]), Point([1, 3, 3]),
+        Point([1, 2, 2]), Point([1, 2, 4]),
+    ], point.neighbours(1));
+    assert_eq!(26, point.neighbours_all(1).len());
+    assert_eq!(8, Point([0, 0]).neighbours_all(1).len());
+
+    let bounds = Bounds::from_points(parse_points::<3>("1,2,3\n-1,5,0\n").unwrap()).unwrap();
+    assert_eq!(Bounds { min: Point([-1, 2, 0]), max: Point([1, 5, 3]) }, bounds);
+    assert!(bounds.contains(&Point([0, 3, 3])));
+    assert!(!bounds.contains(&Point([0, 3, 4])));
+    assert_eq!(3 * 4 * 4, bounds.iter().count());
+    assert_eq!(vec![Point([-3, 0]), Point([-3, 2]), Point([-1, 0]), Point([-1, 2])],
+               Bounds { min: Point([-3, 0]), max: Point([-1, 3]) }.iter_step(2).collect::<Vec<_>>());
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::io;
+use crate::{BoundingBox, Pos};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapRenderer {
+    empty: String,
+    separator: String,
+    row_labels: bool,
+    col_labels: bool,
+    viewport: Option<BoundingBox>,
+    flip_y: bool,
+    footer: Option<String>,
+}
+
+impl Default for MapRenderer {
+    fn default() -> Self {
+        MapRenderer {
+            empty: String::from("."),
+            separator: String::new(),
+            row_labels: false,
+            col_labels: false,
+            viewport: None,
+            flip_y: false,
+            footer: None,
+        }
+    }
+}
+
+impl MapRenderer {
+    pub fn new() -> MapRenderer {
+        MapRenderer::default()
+    }
+
+    pub fn empty(mut self, glyph: &str) -> MapRenderer {
+        self.empty = String::from(glyph);
+        self
+    }
+
+    pub fn separator(mut self, separator: &str) -> MapRenderer {
+        self.separator = String::from(separator);
+        self
+    }
+
+    pub fn row_labels(mut self, enabled: bool) -> MapRenderer {
+        self.row_labels = enabled;
+        self
+    }
+
+    pub fn col_labels(mut self, enabled: bool) -> MapRenderer {
+        self.col_labels = enabled;
+        self
+    }
+
+    //  Renders exactly this window instead of the bounds of the map
+    pub fn viewport(mut self, viewport: BoundingBox) -> MapRenderer {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    //  Renders the largest row on top, for maps where rows grow upwards
+    pub fn flip_y(mut self, enabled: bool) -> MapRenderer {
+        self.flip_y = enabled;
+        self
+    }
+
+    pub fn footer(mut self, footer: &str) -> MapRenderer {
+        self.footer = Some(String::from(footer));
+        self
+    }
+
+    pub fn render<T, W>(&self, map: &HashMap<Pos, T>, out: &mut W) -> fmt::Result where T: Display, W: fmt::Write {
+        let bounds = self.viewport.or_else(|| BoundingBox::of_map(map));
+
+        if let Some(bounds) = bounds {
+            let rows: Vec<i32> = if self.flip_y {
+                (bounds.min_row..=bounds.max_row).rev().collect()
+            } else {
+                (bounds.min_row..=bounds.max_row).collect()
+            };
+
+            let cells: Vec<Vec<String>> = rows.iter()
+                .map(|row| (bounds.min_col..=bounds.max_col)
+                    .map(|col| match map.get(&Pos { row: *row, col }) {
+                        None => self.empty.clone(),
+                        Some(value) => value.to_string()
+                    })
+                    .collect())
+                .collect();
+
+            if self.col_labels {
+                let cell_width = cells.iter()
+                    .flatten()
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(1);
+                self.render_col_labels(&bounds, cell_width, out)?;
+            }
+
+            for (row, row_cells) in rows.iter().zip(cells) {
+                write!(out, "{}", row_cells.join(&self.separator))?;
+                if self.row_labels {
+                    write!(out, "{} {}", self.separator, row)?;
+                }
+                writeln!(out)?;
+            }
+        }
+
+        if let Some(footer) = &self.footer {
+            writeln!(out, "{}", footer)?;
+        }
+        Ok(())
+    }
+
+    pub fn write<T, W>(&self, map: &HashMap<Pos, T>, out: &mut W) -> io::Result<()> where T: Display, W: io::Write {
+        out.write_all(self.render_to_string(map).as_bytes())
+    }
+
+    pub fn render_to_string<T>(&self, map: &HashMap<Pos, T>) -> String where T: Display {
+        let mut buf = String::new();
+        self.render(map, &mut buf).expect("illegal state");
+        buf
+    }
+
+    //  Column numbers are written vertically, one digit per line
+    fn render_col_labels<W>(&self, bounds: &BoundingBox, cell_width: usize, out: &mut W) -> fmt::Result where W: fmt::Write {
+        let labels: Vec<String> = (bounds.min_col..=bounds.max_col).map(|col| col.to_string()).collect();
+        let height = labels.iter().map(|label| label.len()).max().unwrap_or(0);
+        for line in 0..height {
+            let cells: Vec<String> = labels.iter()
+                .map(|label| {
+                    let ch = (line + label.len()).checked_sub(height)
+                        .and_then(|index| label.chars().nth(index))
+                        .unwrap_or(' ');
+                    format!("{:width$}", ch, width = cell_width)
+                })
+                .collect();
+            writeln!(out, "{}", cells.join(&self.separator).trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_render() {
+    let map = HashMap::from([
+        (Pos { row: 0, col: 9 }, '#'),
+        (Pos { row: 1, col: 10 }, '#'),
+    ]);
+
+    assert_eq!("#.\n.#\n", MapRenderer::new().render_to_string(&map));
+
+    let renderer = MapRenderer::new()
+        .empty(" ")
+        .separator("|")
+        .row_labels(true)
+        .col_labels(true)
+        .flip_y(true)
+        .footer("--");
+    assert_eq!(" |1\n9|0\n |#| 1\n#| | 0\n--\n", renderer.render_to_string(&map));
+
+    let clipped = MapRenderer::new()
+        .viewport(BoundingBox { min_row: 1, max_row: 2, min_col: 10, max_col: 11 });
+    assert_eq!("#.\n..\n", clipped.render_to_string(&map));
+}
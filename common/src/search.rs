@@ -0,0 +1,185 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use crate::{Graph, Weight};
+
+//  States from the start to the goal, both included
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchPath<S> {
+    pub path: Vec<S>,
+    pub cost: u32,
+}
+
+//  Best-first exploration shared by the searches below, states are numbered in discovery
+//  order so the queue doesn't need `S: Ord`. Paths costing more than u32::MAX are left out
+pub(crate) struct Explored<S> {
+    pub(crate) states: Vec<S>,
+    pub(crate) cost: Vec<u32>,
+    pub(crate) prev: Vec<Option<usize>>,
+    pub(crate) goal: Option<usize>,
+}
+
+impl<S> Explored<S> where S: Clone {
+    fn path_to(&self, target: usize) -> SearchPath<S> {
+        let mut path = vec![self.states[target].clone()];
+        let mut current = target;
+        while let Some(p) = self.prev[current] {
+            path.push(self.states[p].clone());
+            current = p;
+        }
+        path.reverse();
+        SearchPath { path, cost: self.cost[target] }
+    }
+}
+
+pub(crate) fn explore<S, T, G, N, I, H>(starts: T, is_goal: G, mut neighbours: N, heuristic: H) -> Explored<S>
+    where S: Clone + Eq + Hash, T: IntoIterator<Item=S>, G: Fn(&S) -> bool, N: FnMut(&S) -> I,
+          I: IntoIterator<Item=(S, u32)>, H: Fn(&S) -> u32 {
+    let mut explored = Explored { states: Vec::new(), cost: Vec::new(), prev: Vec::new(), goal: None };
+    let mut index = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        if index.contains_key(&start) {
+            continue;
+        }
+        let u = explored.states.len();
+        index.insert(start.clone(), u);
+        queue.push(Reverse((heuristic(&start), 0, u)));
+        explored.states.push(start);
+        explored.cost.push(0);
+        explored.prev.push(None);
+    }
+
+    while let Some(Reverse((_, g, u))) = queue.pop() {
+        if g > explored.cost[u] {
+            //  Stale queue entry, the state was reached cheaper since
+            continue;
+        }
+        if is_goal(&explored.states[u]) {
+            explored.goal = Some(u);
+            break;
+        }
+
+        for (next, step) in neighbours(&explored.states[u]) {
+            let Some(alt) = g.checked_add(step) else {
+                continue;
+            };
+            let v = match index.get(&next) {
+                Some(v) if alt >= explored.cost[*v] => continue,
+                Some(v) => {
+                    explored.cost[*v] = alt;
+                    explored.prev[*v] = Some(u);
+                    *v
+                }
+                None => {
+                    let v = explored.states.len();
+                    index.insert(next.clone(), v);
+                    explored.states.push(next);
+                    explored.cost.push(alt);
+                    explored.prev.push(Some(u));
+                    v
+                }
+            };
+            queue.push(Reverse((alt.saturating_add(heuristic(&explored.states[v])), alt, v)));
+        }
+    }
+
+    explored
+}
+
+//  A* search. `neighbours` yields the next states with the cost of the step, `heuristic` must
+//  never overestimate the remaining cost to a goal or the returned path may not be the cheapest
+pub fn astar<S, G, N, I, H>(start: S, is_goal: G, neighbours: N, heuristic: H) -> Option<SearchPath<S>>
+    where S: Clone + Eq + Hash, G: Fn(&S) -> bool, N: FnMut(&S) -> I, I: IntoIterator<Item=(S, u32)>, H: Fn(&S) -> u32 {
+    let explored = explore([start], is_goal, neighbours, heuristic);
+    explored.goal.map(|goal| explored.path_to(goal))
+}
+
+//  Cheapest path from any of the start states to the nearest goal
+pub fn dijkstra_until<S, T, G, N, I>(starts: T, is_goal: G, neighbours: N) -> Option<SearchPath<S>>
+    where S: Clone + Eq + Hash, T: IntoIterator<Item=S>, G: Fn(&S) -> bool, N: FnMut(&S) -> I, I: IntoIterator<Item=(S, u32)> {
+    let explored = explore(starts, is_goal, neighbours, |_| 0);
+    explored.goal.map(|goal| explored.path_to(goal))
+}
+
+//  Distances from the nearest start state to every reachable state, and the previous state on
+//  the way there. Start states have no previous state
+pub fn dijkstra_with<S, T, N, I>(starts: T, neighbours: N) -> (HashMap<S, u32>, HashMap<S, S>)
+    where S: Clone + Eq + Hash, T: IntoIterator<Item=S>, N: FnMut(&S) -> I, I: IntoIterator<Item=(S, u32)> {
+    let explored = explore(starts, |_| false, neighbours, |_| 0);
+    let prev = explored.prev.iter()
+        .enumerate()
+        .filter_map(|(v, u)| u.map(|u| (explored.states[v].clone(), explored.states[u].clone())))
+        .collect();
+    let dist = explored.states.into_iter().zip(explored.cost).collect();
+    (dist, prev)
+}
+
+//  A* along outgoing edges, step costs are the edge weights
+pub fn astar_graph<NA, EA, K, H>(graph: &Graph<NA, EA, K>, start: &K, goal: &K, heuristic: H) -> Option<SearchPath<K>>
+    where NA: Clone, EA: Weight, K: Clone + Eq + Hash, H: Fn(&K) -> u32 {
+    let start = graph.index_of(start)?;
+    let goal = graph.index_of(goal)?;
+    let found = astar(
+        start,
+        |u| *u == goal,
+        |u| graph.weighted_successors_at(*u),
+        |u| heuristic(graph.key(*u)))?;
+    Some(SearchPath {
+        path: found.path.into_iter().map(|u| graph.key(u).clone()).collect(),
+        cost: found.cost,
+    })
+}
+
+#[test]
+fn test_astar() {
+    use crate::{NodeId, Pos};
+
+    //  Wall at col 2 with a single gap at the bottom row
+    let walls = [0, 1, 2, 3].map(|row| Pos { row, col: 2 });
+    let inside = |pos: &Pos| (0..5).contains(&pos.row) && (0..5).contains(&pos.col) && !walls.contains(pos);
+    let start = Pos { row: 0, col: 0 };
+    let end = Pos { row: 0, col: 4 };
+
+    let found = astar(
+        start,
+        |pos| *pos == end,
+        |pos| pos.neighbours().into_iter().filter(inside).map(|next| (next, 1)).collect::<Vec<_>>(),
+        |pos| pos.manhattan(&end) as u32,
+    ).unwrap();
+    assert_eq!(12, found.cost);
+    assert_eq!(13, found.path.len());
+    assert_eq!((start, end), (found.path[0], found.path[12]));
+    assert!(found.path.contains(&Pos { row: 4, col: 2 }));
+
+    assert_eq!(None, astar(start, |pos| *pos == Pos { row: 9, col: 9 }, |pos| pos.neighbours().into_iter().filter(inside).map(|next| (next, 1)).collect::<Vec<_>>(), |_| 0));
+
+    let [a, b, c, d] = ["a", "b", "c", "d"].map(NodeId::from);
+    let mut graph: Graph<()> = Graph::from_edges([(a.clone(), b.clone()), (b.clone(), d.clone()), (a.clone(), c.clone())]);
+    graph.insert_weighted_edge(&c, &d, 5);
+    graph.insert_weighted_edge(&a, &b, 3);
+    let found = astar_graph(&graph, &a, &d, |_| 0).unwrap();
+    assert_eq!(SearchPath { path: vec![a.clone(), b, d.clone()], cost: 4 }, found);
+    assert_eq!(None, astar_graph(&graph, &d, &a, |_| 0));
+}
+
+#[test]
+fn test_dijkstra_with() {
+    //  Number line where a step forward costs 1 and jumping to the double costs 3
+    let neighbours = |n: &u32| [(n + 1, 1), (n * 2, 3)].into_iter().filter(|(next, _)| *next <= 20);
+
+    let (dist, prev) = dijkstra_with([1], neighbours);
+    assert_eq!(20, dist.len());
+    assert_eq!(Some(&6), dist.get(&8));
+    assert_eq!(Some(&4), prev.get(&8));
+    assert_eq!(None, prev.get(&1));
+
+    let found = dijkstra_until([3, 9], |n| *n == 19, neighbours).unwrap();
+    assert_eq!(SearchPath { path: vec![9, 18, 19], cost: 4 }, found);
+    assert_eq!(None, dijkstra_until([21], |n| *n == 19, neighbours));
+
+    let (dist, _) = dijkstra_with([0u32], |n: &u32| [(n + 1, u32::MAX)].into_iter().filter(|(next, _)| *next <= 2));
+    assert_eq!(Some(&u32::MAX), dist.get(&1));
+    assert_eq!(None, dist.get(&2));
+}
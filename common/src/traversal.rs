@@ -0,0 +1,328 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
+use crate::{Graph, NodeId};
+
+//  Traversals visit neighbours in index order, i.e. the order nodes were inserted in,
+//  so results are repeatable
+
+pub(crate) fn successors_in_order<NA, EA, K>(graph: &Graph<NA, EA, K>, u: u32) -> Vec<u32> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    let mut successors: Vec<u32> = graph.successors_at(u).iter().map(|(v, _)| *v).collect();
+    successors.sort();
+    successors
+}
+
+//  Reversed so the first item is popped off the stack first
+pub(crate) fn push_in_order<T, I>(stack: &mut Vec<T>, items: I) where I: IntoIterator<Item=T>, I::IntoIter: DoubleEndedIterator {
+    stack.extend(items.into_iter().rev());
+}
+
+//  Breadth-first order of the nodes reachable from the start, following outgoing edges
+pub struct Bfs<'a, NA, EA, K> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    graph: &'a Graph<NA, EA, K>,
+    queue: VecDeque<u32>,
+    seen: Vec<bool>,
+}
+
+impl<'a, NA, EA, K> Iterator for Bfs<'a, NA, EA, K> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.queue.pop_front()?;
+        for v in successors_in_order(self.graph, u) {
+            if !self.seen[v as usize] {
+                self.seen[v as usize] = true;
+                self.queue.push_back(v);
+            }
+        }
+        Some(self.graph.key(u))
+    }
+}
+
+pub fn bfs<'a, NA, EA, K>(graph: &'a Graph<NA, EA, K>, start: &K) -> Bfs<'a, NA, EA, K> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    let mut seen = vec![false; graph.index_bound()];
+    let queue: VecDeque<u32> = graph.index_of(start).into_iter().collect();
+    for u in &queue {
+        seen[*u as usize] = true;
+    }
+    Bfs { graph, queue, seen }
+}
+
+//  Depth-first pre-order of the nodes reachable from the start, following outgoing edges
+pub struct Dfs<'a, NA, EA, K> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    graph: &'a Graph<NA, EA, K>,
+    stack: Vec<u32>,
+    seen: Vec<bool>,
+}
+
+impl<'a, NA, EA, K> Iterator for Dfs<'a, NA, EA, K> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let u = self.stack.pop()?;
+            if self.seen[u as usize] {
+                continue;
+            }
+            self.seen[u as usize] = true;
+            let next: Vec<u32> = successors_in_order(self.graph, u).into_iter().filter(|v| !self.seen[*v as usize]).collect();
+            push_in_order(&mut self.stack, next);
+            return Some(self.graph.key(u));
+        }
+    }
+}
+
+pub fn dfs<'a, NA, EA, K>(graph: &'a Graph<NA, EA, K>, start: &K) -> Dfs<'a, NA, EA, K> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    Dfs {
+        graph,
+        stack: graph.index_of(start).into_iter().collect(),
+        seen: vec![false; graph.index_bound()],
+    }
+}
+
+fn reachable_at<NA, EA, K>(graph: &Graph<NA, EA, K>, start: u32) -> Vec<bool> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    let mut seen = vec![false; graph.index_bound()];
+    seen[start as usize] = true;
+    let mut stack = vec![start];
+    while let Some(u) = stack.pop() {
+        for (v, _) in graph.successors_at(u) {
+            if !seen[*v as usize] {
+                seen[*v as usize] = true;
+                stack.push(*v);
+            }
+        }
+    }
+    seen
+}
+
+//  Includes the start node itself, empty when the start isn't part of the graph
+pub fn reachable<NA, EA, K>(graph: &Graph<NA, EA, K>, start: &K) -> HashSet<K> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    let Some(start) = graph.index_of(start) else {
+        return HashSet::new();
+    };
+    let seen = reachable_at(graph, start);
+    graph.node_indices()
+        .filter(|u| seen[*u as usize])
+        .map(|u| graph.key(u).clone())
+        .collect()
+}
+
+//  Nodes of a cycle in edge order, the last node has an edge back to the first one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<K = NodeId> {
+    pub cycle: Vec<K>,
+}
+
+impl<K> Display for CycleError<K> where K: Display {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let nodes = self.cycle.iter().map(|node| node.to_string()).collect::<Vec<_>>();
+        write!(f, "cycle {} -> {}", nodes.join(" -> "), nodes.first().map_or("", |node| node.as_str()))
+    }
+}
+
+impl<K> Error for CycleError<K> where K: Debug + Display {}
+
+//  Kahn's algorithm, ties are broken by node index
+pub fn topological_sort<NA, EA, K>(graph: &Graph<NA, EA, K>) -> Result<Vec<K>, CycleError<K>> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    let mut in_degree = vec![0; graph.index_bound()];
+    for u in graph.node_indices() {
+        in_degree[u as usize] = graph.predecessors_at(u).len();
+    }
+    let mut ready: BTreeSet<u32> = graph.node_indices().filter(|u| in_degree[*u as usize] == 0).collect();
+
+    let mut order = Vec::with_capacity(graph.node_count());
+    while let Some(u) = ready.pop_first() {
+        for (v, _) in graph.successors_at(u) {
+            in_degree[*v as usize] -= 1;
+            if in_degree[*v as usize] == 0 {
+                ready.insert(*v);
+            }
+        }
+        order.push(graph.key(u).clone());
+    }
+
+    if order.len() == graph.node_count() {
+        return Ok(order);
+    }
+
+    //  Every remaining node has a remaining predecessor, walking them backwards must loop
+    let remaining = |u: &u32| in_degree[*u as usize] > 0;
+    let mut u = graph.node_indices().find(remaining).expect("illegal state");
+    let mut position = vec![None; graph.index_bound()];
+    let mut walk = Vec::new();
+    while position[u as usize].is_none() {
+        position[u as usize] = Some(walk.len());
+        walk.push(u);
+        u = *graph.predecessors_at(u).iter().filter(|p| remaining(p)).min().expect("illegal state");
+    }
+    let mut cycle = walk.split_off(position[u as usize].expect("illegal state"));
+    cycle.reverse();
+    let first = cycle.iter().enumerate().min_by_key(|(_, u)| **u).map_or(0, |(index, _)| index);
+    cycle.rotate_left(first);
+    Err(CycleError { cycle: cycle.into_iter().map(|u| graph.key(u).clone()).collect() })
+}
+
+//  Components when edge directions are ignored
+pub fn weakly_connected_components<NA, EA, K>(graph: &Graph<NA, EA, K>) -> Vec<HashSet<K>> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    let mut seen = vec![false; graph.index_bound()];
+    let mut components = Vec::new();
+    for start in graph.node_indices() {
+        if seen[start as usize] {
+            continue;
+        }
+        seen[start as usize] = true;
+        let mut component = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(u) = stack.pop() {
+            component.insert(graph.key(u).clone());
+            let adjacent = graph.successors_at(u).iter().map(|(v, _)| v)
+                .chain(graph.predecessors_at(u));
+            for v in adjacent {
+                if !seen[*v as usize] {
+                    seen[*v as usize] = true;
+                    stack.push(*v);
+                }
+            }
+        }
+        components.push(component);
+    }
+    components
+}
+
+//  Kosaraju's algorithm, components come in topological order of the condensed graph
+pub fn strongly_connected_components<NA, EA, K>(graph: &Graph<NA, EA, K>) -> Vec<HashSet<K>> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    //  First pass: nodes by DFS finish time along outgoing edges
+    let mut seen = vec![false; graph.index_bound()];
+    let mut finished = Vec::with_capacity(graph.node_count());
+    for start in graph.node_indices() {
+        if seen[start as usize] {
+            continue;
+        }
+        seen[start as usize] = true;
+        let mut stack = vec![(start, graph.successors_at(start).iter())];
+        while let Some((u, next)) = stack.last_mut() {
+            match next.find(|(v, _)| !seen[*v as usize]) {
+                Some((v, _)) => {
+                    seen[*v as usize] = true;
+                    stack.push((*v, graph.successors_at(*v).iter()));
+                }
+                None => {
+                    finished.push(*u);
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    //  Second pass: collect along incoming edges in reverse finish order
+    let mut assigned = vec![false; graph.index_bound()];
+    let mut components = Vec::new();
+    for start in finished.into_iter().rev() {
+        if assigned[start as usize] {
+            continue;
+        }
+        assigned[start as usize] = true;
+        let mut component = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(u) = stack.pop() {
+            component.insert(graph.key(u).clone());
+            for p in graph.predecessors_at(u) {
+                if !assigned[*p as usize] {
+                    assigned[*p as usize] = true;
+                    stack.push(*p);
+                }
+            }
+        }
+        components.push(component);
+    }
+    components
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeError<K = NodeId> {
+    Empty,
+    //  Every node has a parent, like in a pure cycle or when the root has a self-loop
+    NoRoot,
+    MultipleRoots(Vec<K>),
+    MultipleParents(K),
+    //  Nodes that have a parent but can't be reached from the root, i.e. they form a cycle
+    Unreachable(Vec<K>),
+}
+
+impl<K> Display for TreeError<K> where K: Display {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeError::Empty => write!(f, "empty graph"),
+            TreeError::NoRoot => write!(f, "no root, every node has a parent"),
+            TreeError::MultipleRoots(roots) => write!(f, "{} roots, expected one", roots.len()),
+            TreeError::MultipleParents(node) => write!(f, "node {} has more than one parent", node),
+            TreeError::Unreachable(nodes) => write!(f, "{} nodes unreachable from the root", nodes.len()),
+        }
+    }
+}
+
+impl<K> Error for TreeError<K> where K: Debug + Display {}
+
+//  Returns the single root when every other node has exactly one parent and is reachable from it
+pub fn check_tree<NA, EA, K>(graph: &Graph<NA, EA, K>) -> Result<K, TreeError<K>> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    if let Some(u) = graph.node_indices().find(|u| graph.predecessors_at(*u).len() > 1) {
+        return Err(TreeError::MultipleParents(graph.key(u).clone()));
+    }
+
+    let roots: Vec<u32> = graph.node_indices().filter(|u| graph.predecessors_at(*u).is_empty()).collect();
+    let root = match roots.len() {
+        0 if graph.is_empty() => return Err(TreeError::Empty),
+        0 => return Err(TreeError::NoRoot),
+        1 => roots[0],
+        _ => return Err(TreeError::MultipleRoots(roots.into_iter().map(|u| graph.key(u).clone()).collect())),
+    };
+
+    let reached = reachable_at(graph, root);
+    let unreachable: Vec<K> = graph.node_indices()
+        .filter(|u| !reached[*u as usize])
+        .map(|u| graph.key(u).clone())
+        .collect();
+    if !unreachable.is_empty() {
+        return Err(TreeError::Unreachable(unreachable));
+    }
+    Ok(graph.key(root).clone())
+}
+
+#[test]
+fn test_traversal() {
+    let edge = |from: &str, to: &str| (NodeId::from(from), NodeId::from(to));
+    let ids = |ids: &[&str]| ids.iter().map(|id| NodeId::from(id)).collect::<Vec<_>>();
+
+    let tree: Graph<()> = Graph::from_edges([edge("a", "b"), edge("a", "c"), edge("b", "d"), edge("c", "e")]);
+    assert_eq!(ids(&["a", "b", "c", "d", "e"]), bfs(&tree, &NodeId::from("a")).cloned().collect::<Vec<_>>());
+    assert_eq!(ids(&["a", "b", "d", "c", "e"]), dfs(&tree, &NodeId::from("a")).cloned().collect::<Vec<_>>());
+    assert_eq!(HashSet::from_iter(ids(&["c", "e"])), reachable(&tree, &NodeId::from("c")));
+    assert_eq!(Ok(ids(&["a", "b", "c", "d", "e"])), topological_sort(&tree));
+    assert_eq!(Ok(NodeId::from("a")), check_tree(&tree));
+
+    let joined = tree.add_edge(&NodeId::from("b"), &NodeId::from("e"));
+    assert_eq!(Err(TreeError::MultipleParents(NodeId::from("e"))), check_tree(&joined));
+
+    let forest = tree.add_node(&NodeId::from("f"), None);
+    assert_eq!(Err(TreeError::MultipleRoots(ids(&["a", "f"]))), check_tree(&forest));
+    assert_eq!(2, weakly_connected_components(&forest).len());
+
+    let cyclic = Graph::<()>::from_edges([edge("r", "a"), edge("b", "c"), edge("c", "b")]);
+    assert_eq!(Err(TreeError::Unreachable(ids(&["b", "c"]))), check_tree(&cyclic));
+    let cycle = topological_sort(&cyclic).unwrap_err();
+    assert_eq!("cycle b -> c -> b", cycle.to_string());
+    let rootless = Graph::<()>::from_edges([edge("a", "b"), edge("b", "c"), edge("c", "a")]);
+    assert_eq!(Err(TreeError::NoRoot), check_tree(&rootless));
+    assert_eq!(Err(TreeError::NoRoot), check_tree(&tree.add_edge(&NodeId::from("a"), &NodeId::from("a"))));
+    assert_eq!(Err(TreeError::Empty), check_tree(&Graph::<()>::new()));
+
+    let graph = tree
+        .add_edge(&NodeId::from("d"), &NodeId::from("a"))
+        .add_edge(&NodeId::from("e"), &NodeId::from("c"));
+    let components = strongly_connected_components(&graph);
+    assert_eq!(vec![
+        HashSet::from_iter(ids(&["a", "b", "d"])),
+        HashSet::from_iter(ids(&["c", "e"])),
+    ], components);
+}
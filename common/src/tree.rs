@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::{check_tree, Graph, NodeId, TreeError};
+use crate::traversal::{push_in_order, successors_in_order};
+
+//  Rooted view of a graph that passes `check_tree`, children are visited in index order
+//  like the other traversals. Children, orders and depths are computed once when the view is created
+pub struct Tree<'a, NA, EA = u32, K = NodeId> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    graph: &'a Graph<NA, EA, K>,
+    root: u32,
+    parent: Vec<Option<u32>>,
+    //  Sorted by index
+    children: Vec<Vec<u32>>,
+    depth: Vec<usize>,
+    pre_order: Vec<u32>,
+    post_order: Vec<u32>,
+}
+
+impl<'a, NA, EA, K> Tree<'a, NA, EA, K> where NA: Clone, EA: Clone, K: Clone + Eq + Hash {
+    pub fn new(graph: &'a Graph<NA, EA, K>) -> Result<Tree<'a, NA, EA, K>, TreeError<K>> {
+        let root = check_tree(graph)?;
+        let root = graph.index_of(&root).expect("illegal state");
+
+        let mut parent = vec![None; graph.index_bound()];
+        let mut children = vec![Vec::new(); graph.index_bound()];
+        let mut depth = vec![0; graph.index_bound()];
+        let mut pre_order = Vec::with_capacity(graph.node_count());
+        let mut stack = vec![root];
+        while let Some(u) = stack.pop() {
+            pre_order.push(u);
+            children[u as usize] = successors_in_order(graph, u);
+            for v in &children[u as usize] {
+                parent[*v as usize] = Some(u);
+                depth[*v as usize] = depth[u as usize] + 1;
+            }
+            push_in_order(&mut stack, children[u as usize].iter().copied());
+        }
+
+        let mut post_order = Vec::with_capacity(pre_order.len());
+        let mut stack = vec![(root, false)];
+        while let Some((u, expanded)) = stack.pop() {
+            if expanded {
+                post_order.push(u);
+                continue;
+            }
+            stack.push((u, true));
+            push_in_order(&mut stack, children[u as usize].iter().map(|v| (*v, false)));
+        }
+
+        Ok(Tree { graph, root, parent, children, depth, pre_order, post_order })
+    }
+
+    pub fn graph(&self) -> &'a Graph<NA, EA, K> {
+        self.graph
+    }
+
+    pub fn root(&self) -> &'a K {
+        self.graph.key(self.root)
+    }
+
+    //  None for the root and for nodes not in the tree
+    pub fn parent(&self, node: &K) -> Option<&'a K> {
+        let u = self.graph.index_of(node)?;
+        self.parent[u as usize].map(|p| self.graph.key(p))
+    }
+
+    pub fn children(&self, node: &K) -> impl Iterator<Item=&'a K> + '_ {
+        let children = match self.graph.index_of(node) {
+            Some(u) => &self.children[u as usize][..],
+            None => &[],
+        };
+        children.iter().map(|v| self.graph.key(*v))
+    }
+
+    //  0 for the root
+    pub fn depth(&self, node: &K) -> Option<usize> {
+        self.graph.index_of(node).map(|u| self.depth[u as usize])
+    }
+
+    //  Every node before its children
+    pub fn pre_order(&self) -> impl Iterator<Item=&'a K> + '_ {
+        self.pre_order.iter().map(|u| self.graph.key(*u))
+    }
+
+    //  Every node after its children
+    pub fn post_order(&self) -> impl Iterator<Item=&'a K> + '_ {
+        self.post_order.iter().map(|u| self.graph.key(*u))
+    }
+
+    //  Bottom-up value of every subtree, each node is visited once with the values of its children
+    pub fn fold<T, F>(&self, mut f: F) -> HashMap<K, T> where F: FnMut(&K, Option<&NA>, &[&T]) -> T {
+        let mut values: Vec<Option<T>> = (0..self.graph.index_bound()).map(|_| None).collect();
+        for u in self.post_order.iter().copied() {
+            let value = {
+                let children = self.children[u as usize].iter()
+                    .map(|v| values[*v as usize].as_ref().expect("illegal state"))
+                    .collect::<Vec<_>>();
+                f(self.graph.key(u), self.graph.attribute_at(u), &children)
+            };
+            values[u as usize] = Some(value);
+        }
+        self.pre_order.iter()
+            .map(|u| (self.graph.key(*u).clone(), values[*u as usize].take().expect("illegal state")))
+            .collect()
+    }
+}
+
+#[test]
+fn test_tree() {
+    let ids = |ids: &[&str]| ids.iter().map(|id| NodeId::from(id)).collect::<Vec<_>>();
+    let [a, b, c, d, e] = ["a", "b", "c", "d", "e"].map(NodeId::from);
+
+    let mut graph: Graph<u32> = Graph::from_edges([
+        (a.clone(), c.clone()), (a.clone(), b.clone()), (b.clone(), d.clone()), (b.clone(), e.clone()),
+    ]);
+    for (node, size) in [(&c, 3), (&d, 4), (&e, 5)] {
+        graph.insert_node(node, Some(size));
+    }
+
+    let tree = Tree::new(&graph).unwrap();
+    assert_eq!(&a, tree.root());
+    assert_eq!(Some(&b), tree.parent(&e));
+    assert_eq!(None, tree.parent(&a));
+    assert_eq!(vec![&c, &b], tree.children(&a).collect::<Vec<_>>());
+    assert_eq!(Some(2), tree.depth(&d));
+    assert_eq!(Some(0), tree.depth(&a));
+    assert_eq!(ids(&["a", "c", "b", "d", "e"]), tree.pre_order().cloned().collect::<Vec<_>>());
+    assert_eq!(ids(&["c", "d", "e", "b", "a"]), tree.post_order().cloned().collect::<Vec<_>>());
+
+    let mut visits = 0;
+    let sizes = tree.fold(|_, size, children: &[&u32]| {
+        visits += 1;
+        size.copied().unwrap_or(0) + children.iter().copied().sum::<u32>()
+    });
+    assert_eq!(5, visits);
+    assert_eq!(12, sizes[&a]);
+    assert_eq!(9, sizes[&b]);
+    assert_eq!(3, sizes[&c]);
+
+    let cyclic = graph.add_edge(&e, &a);
+    assert!(Tree::new(&cyclic).is_err());
+}
@@ -1,6 +1,5 @@
-use std::collections::HashMap;
 use std::fs;
-use common::{Marker, Pos, print_map};
+use common::{BoundingBox, Grid, Marker, Pos, print_map};
 
 fn main() {
     assert_eq!(13140, solve1("test1"));
@@ -14,7 +13,8 @@ fn solve1(filename: &str) -> i32 {
     let mut x = 1;
     let mut cycle = 1;
 
-    let mut screen: HashMap<Pos, Marker> = HashMap::new();
+    //  40 x 6 pixels, lit ones are Some
+    let mut screen: Grid<Option<Marker>> = Grid::from_fn(BoundingBox { min_row: 0, max_row: 5, min_col: 0, max_col: 39 }, |_| None);
 
     for line in s.lines() {
         if line.eq("noop") {
@@ -33,12 +33,12 @@ fn solve1(filename: &str) -> i32 {
         };
     }
 
-    print_map(&screen);
+    print_map(&screen.to_filled_map());
 
     r
 }
 
-fn tick(cycle: &mut i32, x: i32, r: &mut i32, screen: &mut HashMap<Pos, Marker>) {
+fn tick(cycle: &mut i32, x: i32, r: &mut i32, screen: &mut Grid<Option<Marker>>) {
     *cycle += 1;
     // println!("cycle {} = {}, r = {}", cycle, x, r);
     if (*cycle - 20) % 40 == 0 {
@@ -52,6 +52,9 @@ fn tick(cycle: &mut i32, x: i32, r: &mut i32, screen: &mut HashMap<Pos, Marker>)
     let col = (*cycle - 1) % 40;
 
     if col >= sprite_min_col && col <= sprite_max_col {
-        screen.insert(Pos { row, col }, Marker { visited: true });
+        //  The tick after the last instruction is past the bottom of the screen
+        if let Some(pixel) = screen.get_mut(&Pos { row, col }) {
+            *pixel = Some(Marker { visited: true });
+        }
     }
 }
\ No newline at end of file
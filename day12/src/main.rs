@@ -1,7 +1,6 @@
-use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::HashMap;
 use std::fs;
-use common::{Pos, print_map};
+use common::{astar, dijkstra_until, parse_grid, Pos, print_map};
 
 fn main() {
     assert_eq!(31, solve1("test1"));
@@ -16,21 +15,14 @@ fn solve2(filename: &str) -> usize {
 
     print_map(&map);
 
-    let (dist, prev) = dijkstra(&map, &end.unwrap(), -1);
+    let end = end.expect("bad data");
+    let starts = map.iter()
+        .filter(|(_, ch)| **ch == 'a')
+        .map(|(pos, _)| *pos);
 
-    print_map(&dist);
+    let found = dijkstra_until(starts, |pos| *pos == end, |pos| climb(&map, pos)).expect("no path");
 
-    let mut paths = Vec::new();
-    for (pos, ch) in map {
-        if ch != 'a' { continue; }
-
-        let path = get_path(&prev, &pos);
-        if path.len() != 1 {
-            paths.push(path.len() - 1);
-        }
-    }
-
-    *paths.iter().min().unwrap()
+    found.cost as usize
 }
 
 fn solve1(filename: &str) -> usize {
@@ -38,96 +30,38 @@ fn solve1(filename: &str) -> usize {
 
     print_map(&map);
 
-    let (dist, prev) = dijkstra(&map, &start.unwrap(), 1);
+    let end = end.expect("bad data");
+    let found = astar(
+        start.expect("bad data"),
+        |pos| *pos == end,
+        |pos| climb(&map, pos),
+        |pos| pos.manhattan(&end) as u32,
+    ).expect("no path");
 
-    print_map(&dist);
+    println!("{:?} => {:?}", found.cost, found.path);
 
-    let path = get_path(&prev, &end.unwrap());
-    println!("{:?} => {:?}", path.len(), path);
+    found.cost as usize
+}
 
-    path.len() - 1
+//  At most one step up, any number of steps down
+fn climb(map: &HashMap<Pos, char>, from: &Pos) -> Vec<(Pos, u32)> {
+    let from_ch = map[from];
+    from.neighbours()
+        .into_iter()
+        .filter(|to| map.get(to).is_some_and(|to_ch| *to_ch as i32 - from_ch as i32 <= 1))
+        .map(|to| (to, 1))
+        .collect()
 }
 
 fn parse_map(filename: &str) -> (HashMap<Pos, char>, Option<Pos>, Option<Pos>) {
     let s = fs::read_to_string(filename).expect("bad input");
 
-    let mut map: HashMap::<Pos, char> = HashMap::new();
-
-    let mut start: Option<Pos> = None;
-    let mut end: Option<Pos> = None;
-
-    let mut row = 0;
-    for line in s.lines() {
-        let mut col = 0;
-        for mut ch in line.chars() {
-            let pos = Pos { row, col };
-            if ch == 'S' {
-                ch = 'a';
-                start = Some(pos);
-            } else if ch == 'E' {
-                ch = 'z';
-                end = Some(pos);
-            }
-            map.insert(pos, ch);
-            col += 1;
-        }
-        row += 1;
-    }
-    (map, start, end)
-}
-
-fn get_path(prev: &HashMap<Pos, Pos>, from: &Pos) -> Vec<Pos> {
-    let mut path = Vec::new();
-    path.push(from.clone());
-    while prev.contains_key(&path.last().unwrap()) {
-        path.push(prev[&path.last().unwrap()]);
-    }
-    path
-}
+    let (map, markers) = parse_grid(&s, &['S', 'E'], |ch| match ch {
+        'S' => Some('a'),
+        'E' => Some('z'),
+        'a'..='z' => Some(ch),
+        _ => None
+    }).expect("bad data");
 
-fn dijkstra<'a>(map: &HashMap<Pos, char>, start: &Pos, gradient: i32) -> (HashMap<Pos, u32>, HashMap<Pos, Pos>) {
-    let mut dist = HashMap::new();
-    let mut prev = HashMap::new();
-    let mut queue = Vec::new();
-
-    for (v_pos, _) in map {
-        dist.insert(v_pos.clone(), u32::MAX);
-        queue.push(v_pos.clone());
-    }
-
-    dist.insert(start.clone(), 0);
-
-    while !queue.is_empty() {
-        let min_index = queue.iter()
-            .enumerate()
-            .min_by(|(_, a), (_, b)| dist[a].cmp(&dist[b]))
-            .map(|(index, _)| index)
-            .unwrap();
-
-        let u = queue.remove(min_index);
-        let u_ch = map[&u];
-
-        for d in u.neighbours() {
-            if !queue.contains(&d) { continue; }
-            let d_ch = map[&d];
-            let diff = gradient * d_ch as i32 - gradient * u_ch as i32;
-            if diff > 1 {
-                //  constraint
-                continue;
-            }
-            if dist[&u] == u32::MAX {
-                //  TODO Why is this happening? Unreachable because of other constraints?
-                // print_map(&dist);
-                // panic!("{}", dbg!(&u));
-                continue;
-            }
-            let alt = dist[&u] + 1;
-            if alt < dist[&d] {
-                dist.insert(d.clone(), alt);
-                prev.insert(d.clone(), u.clone());
-            }
-        }
-    }
-
-    (dist, prev)
+    (map.into(), markers.first('S'), markers.first('E'))
 }
@@ -1,9 +1,15 @@
 use std::collections::HashMap;
 use std::fs;
-use common::{map_size, Pos, print_map, Size};
+use std::io::Stdout;
+use common::{BoundingBox, Direction, FrameSink, Grid, Pos, print_map, TerminalPlayer};
 
 static SAND_SOURCE: Pos = Pos { row: 0, col: 500 };
 
+//  `ANIMATE=<fps> cargo run` shows the sand falling around the last grain that came to rest
+fn frames() -> Option<TerminalPlayer<Stdout>> {
+    TerminalPlayer::from_env().map(|player| player.follow(40, 100))
+}
+
 fn main() {
     assert_eq!(24, solve1("test1"));
     assert_eq!(625, solve1("input1"));
@@ -17,60 +23,62 @@ fn solve2(filename: &str) -> usize {
 
     let mut map = parse_map(s);
 
-    let size = &map_size(&map);
+    let size = &BoundingBox::of_map(&map).expect("bad data");
 
     for col in SAND_SOURCE.col - size.max_row - 2..=SAND_SOURCE.col + size.max_row + 2 {
         map.insert(Pos { row: size.max_row + 2, col }, '#');
     }
 
-    let size = &map_size(&map);
+    //  The floor is as wide as the sand can spread, so the cave has fixed bounds from here on
+    let mut cave = Grid::from(&map);
+    let size = &cave.bounds().clone();
 
+    let mut frames = frames();
     while drop_grain_of_sand(
-        &mut map,
+        &mut cave,
         size,
-        SAND_SOURCE) {}
+        SAND_SOURCE,
+        &mut frames) {}
 
-    print_map(&map);
+    print_map(&cave.to_filled_map());
 
-    map.values().filter(|v| **v == 'o').count()
+    count_sand(&cave)
 }
 
 fn solve1(filename: &str) -> usize {
     let s = fs::read_to_string(filename).unwrap();
 
-    let mut map = parse_map(s);
-
-    let size = &map_size(&map);
+    //  Sand leaving the bounds falls forever, cells outside read as empty
+    let mut cave = Grid::from(&parse_map(s));
+    let size = &cave.bounds().clone();
 
+    let mut frames = frames();
     while drop_grain_of_sand(
-        &mut map,
+        &mut cave,
         size,
-        SAND_SOURCE) {}
+        SAND_SOURCE,
+        &mut frames) {}
 
-    map.values().filter(|v| **v == 'o').count()
+    count_sand(&cave)
 }
 
-fn drop_grain_of_sand(map: &mut HashMap<Pos, char>, map_size: &Size, mut sand_pos: Pos) -> bool {
-    for row in 1..=map_size.max_row {
-        sand_pos.row = row;
-        if !map.contains_key(&sand_pos) {
-            continue;
-        }
-        sand_pos.col -= 1;
-        if !map.contains_key(&sand_pos) {
-            continue;
-        }
-        sand_pos.col += 2;
-        if !map.contains_key(&sand_pos) {
-            continue;
-        }
-        sand_pos.row -= 1;
-        sand_pos.col -= 1;
-        map.insert(sand_pos, 'o');
-        if sand_pos.eq(&SAND_SOURCE) {
-            return false;
+fn count_sand(cave: &Grid<Option<char>>) -> usize {
+    cave.iter().filter(|(_, v)| **v == Some('o')).count()
+}
+
+fn drop_grain_of_sand(cave: &mut Grid<Option<char>>, bounds: &BoundingBox, mut sand_pos: Pos, frames: &mut impl FrameSink<char>) -> bool {
+    let moves = [Direction::Bottom, Direction::BottomLeft, Direction::BottomRight];
+    while sand_pos.row < bounds.max_row {
+        match moves.iter()
+            .map(|direction| sand_pos.step(*direction, 1))
+            .find(|next| cave.get(next).is_none_or(|v| v.is_none())) {
+            Some(next) => sand_pos = next,
+            None => {
+                cave[sand_pos] = Some('o');
+                frames.push_grid(cave, Some(sand_pos));
+                return !sand_pos.eq(&SAND_SOURCE);
+            }
         }
-        return true;
     }
     false
 }
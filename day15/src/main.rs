@@ -1,8 +1,6 @@
-use Ordering::Equal;
-use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use common::{map_size, Pos, print_map};
+use common::{BoundingBox, IntervalSet, Pos, print_map};
 use regex::Regex;
 
 fn main() {
@@ -13,34 +11,31 @@ fn main() {
     assert_eq!(10621647166538, solve2("input1", 0, 4_000_000));
 }
 
-fn solve2(filename: &str, from_row: i32, to_row: i32) -> i64 {
+//  The distress beacon has both its row and its column within `min..=max`
+fn solve2(filename: &str, min: i32, max: i32) -> i64 {
     let (map, sensor_beacon_pairs) = parse_map(filename);
 
-    if map_size(&map).max_col < 100 {
+    if BoundingBox::of_map(&map).expect("bad data").max_col < 100 {
         print_map(&map);
     }
 
-    for target_row in from_row..=to_row {
+    let columns = IntervalSet::from(min..=max);
+
+    for target_row in min..=max {
         let ranges = build_ranges(target_row, &sensor_beacon_pairs);
 
-        let mut col = ranges.first().unwrap().0;
-        for (start, end) in &ranges {
-            if start > &col {
-                return ((*start as i64 - 1) * 4000000) + target_row as i64
-            }
-            if end > &col {
-                col = *end;
-            }
+        if let Some(hole) = columns.difference(&ranges).iter().next() {
+            return (*hole.start() as i64 * 4000000) + target_row as i64
         }
     }
 
     panic!("no solution")
 }
 
-fn solve1(filename: &str, target_row: i32) -> i32 {
+fn solve1(filename: &str, target_row: i32) -> u128 {
     let (map, sensor_beacon_pairs) = parse_map(filename);
 
-    if map_size(&map).max_col < 100 {
+    if BoundingBox::of_map(&map).expect("bad data").max_col < 100 {
         print_map(&map);
     }
 
@@ -48,38 +43,24 @@ fn solve1(filename: &str, target_row: i32) -> i32 {
 
     println!("{:?}", ranges);
 
-    let mut count = 0;
-    let mut col = ranges.first().unwrap().0;
-    for (start, end) in &ranges {
-        if start > &col {
-            panic!("should be no holes");
-        }
-        if end > &col {
-            count += end - &col;
-            col = *end;
-        }
-    }
+    let beacons = sensor_beacon_pairs.iter()
+        .map(|(_, b)| b)
+        .filter(|b| b.row == target_row && ranges.contains(b.col))
+        .collect::<HashSet<_>>();
 
-    count
+    ranges.len() - beacons.len() as u128
 }
 
-fn build_ranges(target_row: i32, sensor_beacon_pairs: &Vec<(Pos, Pos)>) -> Vec<(i32, i32)> {
-    let mut ranges = Vec::new();
+fn build_ranges(target_row: i32, sensor_beacon_pairs: &Vec<(Pos, Pos)>) -> IntervalSet<i32> {
+    let mut ranges = IntervalSet::new();
     sensor_beacon_pairs.iter().for_each(|(s, b)| {
-        let distance = manhattan_distance(s, b);
+        let distance = s.manhattan(b);
 
         if s.row - distance <= target_row && s.row + distance >= target_row {
             capture_ranges(target_row, &mut ranges, s, distance);
         }
     });
 
-    ranges.sort_by(|(s1, e1), (s2, e2)| {
-        let ordering = s1.cmp(s2);
-        match ordering {
-            Equal => e1.cmp(e2),
-            _ => ordering
-        }
-    });
     ranges
 }
 
@@ -112,11 +93,7 @@ fn parse_map(filename: &str) -> (HashMap<Pos, char>, Vec<(Pos, Pos)>) {
     (map, sensor_beacon_pairs)
 }
 
-fn manhattan_distance(a: &Pos, b: &Pos) -> i32 {
-    i32::abs(a.col - b.col) + i32::abs(a.row - b.row)
-}
-
-fn capture_ranges(target_row: i32, ranges: &mut Vec<(i32, i32)>, s: &Pos, distance: i32) {
+fn capture_ranges(target_row: i32, ranges: &mut IntervalSet<i32>, s: &Pos, distance: i32) {
     if target_row >= s.row {
         let d = distance - (target_row - s.row);
         let p1 = Pos {
@@ -127,7 +104,7 @@ fn capture_ranges(target_row: i32, ranges: &mut Vec<(i32, i32)>, s: &Pos, distan
             col: s.col + d,
             row: target_row,
         };
-        ranges.push((p1.col, p3.col));
+        ranges.insert(p1.col..=p3.col);
     } else {
         //  target_row <= s.row
         let d = distance - (s.row - target_row);
@@ -139,7 +116,7 @@ fn capture_ranges(target_row: i32, ranges: &mut Vec<(i32, i32)>, s: &Pos, distan
             col: s.col + d,
             row: target_row,
         };
-        ranges.push((p2.col, p4.col));
+        ranges.insert(p2.col..=p4.col);
     }
 }
 
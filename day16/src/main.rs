@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 use std::fs;
-use common::{create_dot_file2, dijkstra, get_path, Graph, NodeId};
+use common::{create_graph_file, DistanceMatrix, DotRenderer, Graph, GraphFormat, NodeId};
 use regex::Regex;
 
 fn main() {
@@ -15,7 +15,9 @@ fn main() {
 #[derive(Clone, Debug)]
 struct State {
     releasing_pressure: u32,
-    segments: Vec<Vec<NodeId>>,
+    //  In the order they were opened
+    opened: Vec<NodeId>,
+    time_left: u32,
 }
 
 #[derive(Clone)]
@@ -33,20 +35,19 @@ fn solve2(filename: &str) -> u32 {
     let graph = parse_graph(filename);
 
     let from = NodeId::from("AA");
+    let distances = valve_distances(&graph, &from);
 
     let mut all_states = create_states(
         &graph,
-        &vec![],
+        &distances,
         &from,
-        &HashSet::new(),
-        0,
-        26);
+        &State { releasing_pressure: 0, opened: vec![], time_left: 26 });
 
     all_states.sort_by(|a, b| a.releasing_pressure.cmp(&b.releasing_pressure));
 
     let mut max = 0;
     for path1 in all_states.iter().rev() {
-        if let Some(path2) = complement(&all_states, flatten(&path1.segments)) {
+        if let Some(path2) = complement(&all_states, path1.opened.iter().cloned().collect()) {
             let total_pressure = path1.releasing_pressure + path2.releasing_pressure;
             if max < total_pressure {
                 max = total_pressure;
@@ -57,13 +58,13 @@ fn solve2(filename: &str) -> u32 {
     max
 }
 
-fn complement(all_states: &Vec<State>, flatten: HashSet<NodeId>) -> Option<&State> {
+fn complement(all_states: &Vec<State>, opened: HashSet<NodeId>) -> Option<&State> {
     all_states
         .iter()
         .rev()
         .find(|state|
-            state.segments.iter()
-                .find(|s| flatten.contains(s.first().unwrap()))
+            state.opened.iter()
+                .find(|node_id| opened.contains(node_id))
                 .is_none())
 }
 
@@ -76,20 +77,20 @@ fn solve1(filename: &str) -> u32 {
             _ => Some(",fillcolor=\"green\",style=\"filled\",fontcolor=\"white\"")
         };
 
-    create_dot_file2(
+    create_graph_file(
         &graph,
+        GraphFormat::PlantUml,
         decorator,
-        &format!("{}.dot", filename));
+        &format!("{}.{}", filename, GraphFormat::PlantUml.extension()));
 
     let from = NodeId::from("AA");
+    let distances = valve_distances(&graph, &from);
 
     let mut all_states = create_states(
         &graph,
-        &vec![],
+        &distances,
         &from,
-        &HashSet::new(),
-        0,
-        30);
+        &State { releasing_pressure: 0, opened: vec![], time_left: 30 });
 
     all_states.sort_by(|a, b| a.releasing_pressure.cmp(&b.releasing_pressure));
 
@@ -97,40 +98,50 @@ fn solve1(filename: &str) -> u32 {
 
     println!("{:?}", tail);
 
+    let mut path = vec![from];
+    path.extend(tail.opened.iter().cloned());
+    DotRenderer::new()
+        .node_style(|_, valve: Option<&Valve>| valve
+            .filter(|valve| valve.rate > 0)
+            .map(|_| [("fillcolor", "green"), ("style", "filled"), ("fontcolor", "white")])
+            .into_iter()
+            .flatten())
+        .highlight_path(&path)
+        .create_file(&graph, &format!("{}.dot", filename));
+
     tail.releasing_pressure
 }
 
+//  Only the start and valves that release pressure are worth walking to,
+//  the others are contracted into weighted tunnels
+fn valve_distances(graph: &Graph<Valve>, from: &NodeId) -> DistanceMatrix {
+    let valves = graph.contract(|node_id, valve| node_id != from && valve.is_none_or(|valve| valve.rate == 0));
+    DistanceMatrix::all_pairs(&valves)
+}
+
 fn create_states(graph: &Graph<Valve>,
-                 path_to_from: &Vec<Vec<NodeId>>,
+                 distances: &DistanceMatrix,
                  from: &NodeId,
-                 exclude: &HashSet<NodeId>,
-                 previous_pressure_released: u32,
-                 time_budget: u32) -> Vec<State> {
-
-    let (_, prev) = dijkstra(&graph, &from);
+                 state: &State) -> Vec<State> {
 
     let mut new_states = Vec::new();
 
-    for node_id in &graph.nodes {
-        let attr = &graph.node_attributes[node_id];
-        if attr.rate > 0 && !exclude.contains(node_id) {
-            let path = get_path(&prev, node_id);
-            let mut path_segments = Vec::new();
-            for p in path_to_from {
-                path_segments.push(p.clone());
-            }
-            path_segments.push(path);
-            let valves_visited = path_segments.iter().map(|p| p.len() as u32 - 1).sum::<u32>();
-            let valves_opened = path_segments.len() as u32;
-            let time_spent = valves_visited + valves_opened;
-            if time_spent > time_budget {
+    for node_id in distances.nodes() {
+        let attr = graph.attribute(node_id).expect("bad data");
+        if attr.rate > 0 && !state.opened.contains(node_id) {
+            //  Walk to the valve and open it
+            let time_spent = distances.distance(from, node_id).expect("bad data") + 1;
+            if time_spent > state.time_left {
                 continue;
             }
-            let releasing_pressure = previous_pressure_released + attr.rate * (time_budget - time_spent);
+            let time_left = state.time_left - time_spent;
+            let mut opened = state.opened.clone();
+            opened.push(node_id.clone());
 
             new_states.push(State {
-                segments: path_segments,
-                releasing_pressure,
+                releasing_pressure: state.releasing_pressure + attr.rate * time_left,
+                opened,
+                time_left,
             });
         }
     }
@@ -140,12 +151,10 @@ fn create_states(graph: &Graph<Valve>,
     for state in new_states.iter() {
         let child_states =
             create_states(
-                &graph,
-                &state.segments,
-                &state.segments.last().unwrap().first().unwrap(),
-                &flatten(&state.segments),
-                state.releasing_pressure,
-                time_budget,
+                graph,
+                distances,
+                state.opened.last().unwrap(),
+                state,
             );
 
         for child_state in child_states {
@@ -160,30 +169,25 @@ fn create_states(graph: &Graph<Valve>,
     new_states
 }
 
-fn flatten(segments: &Vec<Vec<NodeId>>) -> HashSet<NodeId> {
-    segments.iter()
-        .map(|s| s.first().unwrap().clone())
-        .collect()
-}
-
 fn parse_graph(filename: &str) -> Graph<Valve> {
     let s = fs::read_to_string(filename).unwrap();
 
     let pattern =
         Regex::new("Valve (\\w+) has flow rate=(\\d+); tunnels? leads? to valves? (.*)").unwrap();
 
-    let mut graph = Graph::new();
+    //  Tunnels lead both ways
+    let mut graph = Graph::undirected();
 
     for line in s.lines() {
         let matcher = pattern.captures(line).unwrap();
         let node_id = NodeId::from(&matcher[1]);
         let valve = Valve { rate: matcher[2].parse().unwrap() };
-        graph = graph.add_node(&node_id, Some(valve));
+        graph.insert_node(&node_id, Some(valve));
 
         for target_node_id in matcher[3].split(", ") {
             let target_node_id = NodeId::from(target_node_id);
-            graph = graph.add_node(&target_node_id, None);
-            graph = graph.add_edge(&node_id, &target_node_id);
+            graph.insert_node(&target_node_id, None);
+            graph.insert_edge(&node_id, &target_node_id);
         }
     }
 
@@ -2,7 +2,7 @@ extern crate core;
 
 use std::collections::HashMap;
 use std::ops::{Neg, Range, RangeInclusive};
-use common::{map_size, Pos, print_map, Size};
+use common::{BoundedMap, FrameSink, NoFrames, parse_grid, Pos, print_map};
 
 type Figure = HashMap<Pos, char>;
 
@@ -26,8 +26,8 @@ fn main() {
 }
 
 fn solve2(figures: &Vec<Figure>, total_figures: u64, instr: &str, probe_size: usize) -> u64 {
-    let (field, row_details) = play(&figures, instr, probe_size);
-    let size = map_size(&field);
+    let (field, row_details) = play(&figures, instr, probe_size, &mut NoFrames);
+    let size = field.bounds().expect("bad data");
     let rows_in_period = find_period(&field, 10, size.min_row / 2);
     println!("rows_in_period={}", rows_in_period);
 
@@ -65,19 +65,19 @@ fn solve2(figures: &Vec<Figure>, total_figures: u64, instr: &str, probe_size: us
     println!("full_periods={}", full_periods);
     println!("rows in full_periods={}", full_periods * rows_in_period as u64);
 
-    let (mut field, mut row_details) = play(&figures, instr, (offset_in_figures + period_in_figures as u64) as usize);
+    let (mut field, mut row_details) = play(&figures, instr, (offset_in_figures + period_in_figures as u64) as usize, &mut NoFrames);
     print_map(&field);
 
-    let size_before_continuation = map_size(&field);
+    let size_before_continuation = field.bounds().expect("bad data");
     let continuation = row_details_at_offset_and_period.clone();
 
     play_from_state(&figures, instr, moves_after_full_periods as usize, &mut field, &mut row_details, &RowDetails {
         figures_dropped: continuation.figures_dropped,
         figure_index: continuation.figure_index + 1,
         instr_index: continuation.instr_index + 1
-    });
+    }, &mut NoFrames);
 
-    let size = map_size(&field);
+    let size = field.bounds().expect("bad data");
     let latest_details = row_details.get(&size.min_row).unwrap();
     println!("size_before_continuation={:?}, size={:?}, latest_details={:?}", size_before_continuation, size, latest_details);
 
@@ -88,16 +88,16 @@ fn solve2(figures: &Vec<Figure>, total_figures: u64, instr: &str, probe_size: us
 }
 
 fn solve1(figures: &Vec<Figure>, instr: &str) -> i32 {
-    let (field, _) = play(&figures, instr, 2022);
+    let (field, _) = play(&figures, instr, 2022, &mut NoFrames);
 
-    let size = map_size(&field);
+    let size = field.bounds().expect("bad data");
 
     // print_map(&field);
     i32::abs(size.min_row)
 }
 
-fn find_period(field: &HashMap<Pos, char>, min: i32, starting_from: i32) -> i32 {
-    let size = map_size(field);
+fn find_period(field: &BoundedMap<char>, min: i32, starting_from: i32) -> i32 {
+    let size = field.bounds().expect("bad data");
     let mut period = min;
     loop {
         let range1 = starting_from..(starting_from + period);
@@ -143,15 +143,15 @@ struct RowDetails {
     figure_index: usize,
 }
 
-fn play(figures: &Vec<Figure>, instr: &str, move_count: usize) -> (HashMap<Pos, char>, HashMap<i32, RowDetails>) {
-    let mut field = HashMap::new();
+fn play(figures: &Vec<Figure>, instr: &str, move_count: usize, frames: &mut impl FrameSink<char>) -> (BoundedMap<char>, HashMap<i32, RowDetails>) {
+    let mut field = BoundedMap::new();
     for col in 0..7 {
         field.insert(Pos { row: 0, col }, '~');
     }
 
     let mut row_details = HashMap::<i32, RowDetails>::new();
 
-    play_from_state(&figures, instr, move_count, &mut field, &mut row_details, &RowDetails { figure_index: 0, figures_dropped: 0, instr_index: 0 });
+    play_from_state(&figures, instr, move_count, &mut field, &mut row_details, &RowDetails { figure_index: 0, figures_dropped: 0, instr_index: 0 }, frames);
 
     (field, row_details)
 }
@@ -159,9 +159,10 @@ fn play(figures: &Vec<Figure>, instr: &str, move_count: usize) -> (HashMap<Pos,
 fn play_from_state(figures: &Vec<Figure>,
                    instr: &str,
                    move_count: usize,
-                   field: &mut HashMap<Pos, char>,
+                   field: &mut BoundedMap<char>,
                    row_details: &mut HashMap<i32, RowDetails>,
-                   state: &RowDetails) {
+                   state: &RowDetails,
+                   frames: &mut impl FrameSink<char>) {
     if move_count == 0 {
         return;
     }
@@ -170,7 +171,7 @@ fn play_from_state(figures: &Vec<Figure>,
     let mut figure_index = state.figure_index;
     let mut figures_dropped = state.figures_dropped;
     let mut instr_index = state.instr_index;
-    let mut offset = start_pos + Pos { row: map_size(&field).min_row, col: 0 };
+    let mut offset = start_pos + Pos { row: field.bounds().expect("bad data").min_row, col: 0 };
     let mut move_counter = 0;
     loop {
         let figure = &figures[figure_index % figures.len()];
@@ -189,7 +190,9 @@ fn play_from_state(figures: &Vec<Figure>,
         } else {
             add_to_field(field, &offset, figure);
 
-            let size = map_size(&field);
+            let size = field.bounds().expect("bad data");
+
+            frames.push(field, Some(Pos { row: size.min_row, col: 3 }));
 
             figures_dropped += 1;
 
@@ -224,7 +227,7 @@ fn collides(field: &HashMap<Pos, char>, offset: &Pos, figure: &Figure) -> bool {
     false
 }
 
-fn add_to_field(field: &mut HashMap<Pos, char>, offset: &Pos, figure: &Figure) {
+fn add_to_field(field: &mut BoundedMap<char>, offset: &Pos, figure: &Figure) {
     for (key, value) in figure {
         let in_field_pos = *offset + *key;
         field.insert(in_field_pos, value.clone());
@@ -234,41 +237,41 @@ fn add_to_field(field: &mut HashMap<Pos, char>, offset: &Pos, figure: &Figure) {
 fn figures() -> Vec<Figure> {
     vec![
         //  HLine
-        HashMap::from([
-            (Pos { row: 0, col: 0 }, '@'),
-            (Pos { row: 0, col: 1 }, '@'),
-            (Pos { row: 0, col: 2 }, '@'),
-            (Pos { row: 0, col: 3 }, '@'),
-        ]),
+        figure("####"),
         //  Plus
-        HashMap::from([
-            (Pos { row: 0, col: 1 }, '@'),
-            (Pos { row: -1, col: 0 }, '@'),
-            (Pos { row: -1, col: 1 }, '@'),
-            (Pos { row: -1, col: 2 }, '@'),
-            (Pos { row: -2, col: 1 }, '@'),
-        ]),
+        figure("
+            .#.
+            ###
+            .#."),
         //  Reverse L
-        HashMap::from([
-            (Pos { row: 0, col: 0 }, '@'),
-            (Pos { row: 0, col: 1 }, '@'),
-            (Pos { row: 0, col: 2 }, '@'),
-            (Pos { row: -1, col: 2 }, '@'),
-            (Pos { row: -2, col: 2 }, '@'),
-        ]),
+        figure("
+            ..#
+            ..#
+            ###"),
         //  VLine
-        HashMap::from([
-            (Pos { row: 0, col: 0 }, '@'),
-            (Pos { row: -1, col: 0 }, '@'),
-            (Pos { row: -2, col: 0 }, '@'),
-            (Pos { row: -3, col: 0 }, '@'),
-        ]),
+        figure("
+            #
+            #
+            #
+            #"),
         //  Square
-        HashMap::from([
-            (Pos { row: 0, col: 0 }, '@'),
-            (Pos { row: 0, col: 1 }, '@'),
-            (Pos { row: -1, col: 0 }, '@'),
-            (Pos { row: -1, col: 1 }, '@'),
-        ]),
+        figure("
+            ##
+            ##"),
     ]
 }
+
+fn figure(shape: &str) -> Figure {
+    let (grid, _) = parse_grid(shape, &[], |ch| match ch {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None
+    }).expect("bad figure");
+
+    //  Bottom row of a figure is 0, rows above it are negative
+    let bottom = grid.bounds().max_row;
+    grid.iter()
+        .filter(|(_, solid)| **solid)
+        .map(|(pos, _)| (pos + Pos { row: -bottom, col: 0 }, '@'))
+        .collect()
+}
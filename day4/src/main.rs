@@ -1,4 +1,5 @@
 use std::{fs, ops::RangeInclusive};
+use common::IntervalSet;
 
 #[test]
 fn test_part1() {
@@ -52,11 +53,11 @@ fn part2(filename: &str) -> u32 {
 }
 
 fn contains_all(range1: &RangeInclusive<u32>, range2: &RangeInclusive<u32>) -> bool {
-    range1.clone().all(|i| range2.contains(&i))
+    IntervalSet::from(range2.clone()).contains_range(range1)
 }
 
 fn contains_any(range1: &RangeInclusive<u32>, range2: &RangeInclusive<u32>) -> bool {
-    range1.clone().any(|i| range2.contains(&i))
+    IntervalSet::from(range2.clone()).overlaps(range1)
 }
 
 #[test]
@@ -1,9 +1,8 @@
 extern crate core;
 
-use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
 use std::fs;
-use common::{map_size, Pos, print_map, Size};
+use common::{BoundingBox, Grid, parse_grid, Pos, print_map};
 
 
 fn main() {
@@ -19,9 +18,9 @@ fn main() {
 fn solve2(filename: &str) -> u32 {
     let map = parse_map(filename);
 
-    print_map(&map);
+    print_map(&map.to_map());
 
-    let size = map_size(&map);
+    let size = *map.bounds();
 
     let mut max_score = 0;
     for row in size.min_row..=size.max_row {
@@ -37,11 +36,11 @@ fn solve2(filename: &str) -> u32 {
     max_score
 }
 
-fn scenic_score(map: &HashMap<Pos, State>, pos: &Pos, size: &Size) -> u32 {
-    let left_to_right_view = left_to_right_views(&Size { min_row: pos.row, max_row: pos.row, min_col: pos.col, max_col: size.max_col });
-    let right_to_left_view = right_to_left_views(&Size { min_row: pos.row, max_row: pos.row, min_col: size.min_col, max_col: pos.col });
-    let top_to_bottom_view = top_to_bottom_views(&Size { min_row: pos.row, max_row: size.max_row, min_col: pos.col, max_col: pos.col });
-    let bottom_to_top_view = bottom_to_top_views(&Size { min_row: size.min_row, max_row: pos.row, min_col: pos.col, max_col: pos.col });
+fn scenic_score(map: &Grid<State>, pos: &Pos, size: &BoundingBox) -> u32 {
+    let left_to_right_view = left_to_right_views(&BoundingBox { min_row: pos.row, max_row: pos.row, min_col: pos.col, max_col: size.max_col });
+    let right_to_left_view = right_to_left_views(&BoundingBox { min_row: pos.row, max_row: pos.row, min_col: size.min_col, max_col: pos.col });
+    let top_to_bottom_view = top_to_bottom_views(&BoundingBox { min_row: pos.row, max_row: size.max_row, min_col: pos.col, max_col: pos.col });
+    let bottom_to_top_view = bottom_to_top_views(&BoundingBox { min_row: size.min_row, max_row: pos.row, min_col: pos.col, max_col: pos.col });
 
     let left_to_right_score = view_score(map, pos, &left_to_right_view[0]);
     let right_to_left_score = view_score(map, pos, &right_to_left_view[0]);
@@ -51,7 +50,7 @@ fn scenic_score(map: &HashMap<Pos, State>, pos: &Pos, size: &Size) -> u32 {
     left_to_right_score * right_to_left_score * top_to_bottom_score * bottom_to_top_score
 }
 
-fn view_score(map: &HashMap<Pos, State>, pos: &Pos, view: &Vec<Pos>) -> u32 {
+fn view_score(map: &Grid<State>, pos: &Pos, view: &Vec<Pos>) -> u32 {
     let self_size = map.get(pos).expect("bad state").size;
     let mut count = 0;
     for next in view {
@@ -84,19 +83,19 @@ impl Display for State {
 fn solve1(filename: &str) -> u32 {
     let map = parse_map(filename);
 
-    print_map(&map);
+    print_map(&map.to_map());
 
-    let size = map_size(&map);
+    let size = *map.bounds();
 
     let new_map = scan_map(&map, left_to_right_views(&size), Pos::left);
     let new_map = scan_map(&new_map, right_to_left_views(&size), Pos::right);
     let new_map = scan_map(&new_map, top_to_bottom_views(&size), Pos::top);
     let new_map = scan_map(&new_map, bottom_to_top_views(&size), Pos::bottom);
 
-    print_map(&new_map);
+    print_map(&new_map.to_map());
 
     let mut count = 0;
-    for (_, state) in new_map {
+    for (_, state) in new_map.iter() {
         if state.visible {
             count += 1;
         }
@@ -105,7 +104,7 @@ fn solve1(filename: &str) -> u32 {
     count
 }
 
-fn bottom_to_top_views(size: &Size) -> Vec<Vec<Pos>> {
+fn bottom_to_top_views(size: &BoundingBox) -> Vec<Vec<Pos>> {
     let mut views = Vec::new();
     for col in size.min_col..=size.max_col {
         let mut view = Vec::new();
@@ -117,7 +116,7 @@ fn bottom_to_top_views(size: &Size) -> Vec<Vec<Pos>> {
     views
 }
 
-fn top_to_bottom_views(size: &Size) -> Vec<Vec<Pos>> {
+fn top_to_bottom_views(size: &BoundingBox) -> Vec<Vec<Pos>> {
     let mut views = Vec::new();
     for col in size.min_col..=size.max_col {
         let mut view = Vec::new();
@@ -129,7 +128,7 @@ fn top_to_bottom_views(size: &Size) -> Vec<Vec<Pos>> {
     views
 }
 
-fn right_to_left_views(size: &Size) -> Vec<Vec<Pos>> {
+fn right_to_left_views(size: &BoundingBox) -> Vec<Vec<Pos>> {
     let mut views = Vec::new();
     for row in size.min_row..=size.max_row {
         let mut view = Vec::new();
@@ -141,7 +140,7 @@ fn right_to_left_views(size: &Size) -> Vec<Vec<Pos>> {
     views
 }
 
-fn left_to_right_views(size: &Size) -> Vec<Vec<Pos>> {
+fn left_to_right_views(size: &BoundingBox) -> Vec<Vec<Pos>> {
     let mut views = Vec::new();
     for row in size.min_row..=size.max_row {
         let mut view = Vec::new();
@@ -154,11 +153,11 @@ fn left_to_right_views(size: &Size) -> Vec<Vec<Pos>> {
 }
 
 fn scan_map(
-    map: &HashMap<Pos, State>,
+    map: &Grid<State>,
     views: Vec<Vec<Pos>>,
     f_neighbour: fn(&Pos) -> Pos,
-) -> HashMap<Pos, State> {
-    let mut new_map: HashMap<Pos, State> = map.clone();
+) -> Grid<State> {
+    let mut new_map: Grid<State> = map.clone();
 
     for view in views {
         let mut max: i32 = -1;
@@ -179,7 +178,7 @@ fn scan_map(
                         visible: state.visible || neighbour_state.size < state.size,
                     }
                 };
-                new_map.insert(pos, new_state);
+                new_map[pos] = new_state;
             };
         }
     }
@@ -187,22 +186,13 @@ fn scan_map(
     new_map
 }
 
-fn parse_map(filename: &str) -> HashMap<Pos, State> {
-    let mut map = HashMap::new();
-
+fn parse_map(filename: &str) -> Grid<State> {
     let s = fs::read_to_string(filename).expect("bad input");
 
-    let mut row_index = 0;
-    for line in s.lines() {
-        for col_index in 0..line.len() {
-            map.insert(
-                Pos { row: row_index, col: col_index as i32 },
-                State {
-                    size: line[col_index..=col_index].parse().expect("bad data"),
-                    visible: false,
-                });
-        }
-        row_index += 1;
-    }
+    let (map, _) = parse_grid(&s, &[], |ch| ch.to_digit(10).map(|size| State {
+        size,
+        visible: false,
+    })).expect("bad data");
+
     map
-}
\ No newline at end of file
+}
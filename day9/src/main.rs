@@ -2,7 +2,8 @@ extern crate core;
 
 use std::collections::HashMap;
 use std::fs;
-use common::{Marker, Pos, print_map};
+use std::io::Stdout;
+use common::{Direction, FrameSink, Marker, Pos, print_map, TerminalPlayer};
 
 fn main() {
     assert_eq!(13, solve1("test1"));
@@ -17,7 +18,7 @@ fn solve1(filename: &str) -> usize {
 
     let knots = vec![Pos { row: 0, col: 0 }; 2];
 
-    solve(s, knots)
+    solve(s, knots, &mut frames())
 }
 
 fn solve2(filename: &str) -> usize {
@@ -25,22 +26,22 @@ fn solve2(filename: &str) -> usize {
 
     let knots = vec![Pos { row: 0, col: 0 }; 10];
 
-    solve(s, knots)
+    solve(s, knots, &mut frames())
 }
 
-fn solve(s: String, mut knots: Vec<Pos>) -> usize {
+//  `ANIMATE=<fps> cargo run` shows the visited positions around the head of the rope
+fn frames() -> Option<TerminalPlayer<Stdout>> {
+    TerminalPlayer::from_env().map(|player| player.follow(30, 80))
+}
+
+fn solve(s: String, mut knots: Vec<Pos>, frames: &mut impl FrameSink<Marker>) -> usize {
     let mut map: HashMap<Pos, Marker> = HashMap::new();
 
     for line in s.lines() {
         let (direction, count) = line.split_once(" ").expect("bad data");
+        let direction = direction.chars().next().and_then(Direction::from_char).expect("bad data");
         let count: u32 = count.parse().expect("bad data");
-        match direction {
-            "L" => knots = steps(&mut map, count, knots, Pos::left),
-            "U" => knots = steps(&mut map, count, knots, Pos::top),
-            "R" => knots = steps(&mut map, count, knots, Pos::right),
-            "D" => knots = steps(&mut map, count, knots, Pos::bottom),
-            _ => panic!("bad data")
-        }
+        knots = steps(&mut map, count, knots, direction, frames);
     }
 
     print_map(&map);
@@ -52,10 +53,11 @@ fn steps(
     map: &mut HashMap<Pos, Marker>,
     count: u32,
     mut knots: Vec<Pos>,
-    f_next: fn(&Pos) -> Pos,
+    direction: Direction,
+    frames: &mut impl FrameSink<Marker>,
 ) -> Vec<Pos> {
     for _ in 1..=count {
-        let new_head = f_next(&knots.get(0).expect("bad state"));
+        let new_head = knots.get(0).expect("bad state").step(direction, 1);
         let mut new_knots = Vec::with_capacity(knots.len());
         new_knots.push(new_head);
         let mut prev_knot = new_head;
@@ -68,27 +70,12 @@ fn steps(
         map.insert(prev_knot.clone(), Marker { visited: true });
         knots = new_knots;
 
-        // print_map(&map);
+        frames.push(map, Some(new_head));
     }
     // println!("moved {} times\n\n", count);
     knots
 }
 
 fn step(tail: &Pos, new_head: Pos) -> Pos {
-    let mut new_tail_row = tail.row;
-    let mut new_tail_col = tail.col;
-    if (new_head.row - tail.row).abs() +
-        (new_head.col - tail.col).abs() > 2 {
-        new_tail_row = tail.row + (new_head.row - tail.row).signum();
-        new_tail_col = tail.col + (new_head.col - tail.col).signum();
-    } else if (new_head.row - tail.row).abs() > 1 {
-        new_tail_row = tail.row + (new_head.row - tail.row).signum();
-    } else if (new_head.col - tail.col).abs() > 1 {
-        new_tail_col = tail.col + (new_head.col - tail.col).signum();
-    }
-    Pos {
-        row: new_tail_row,
-        col: new_tail_col,
-    }
+    if tail.chebyshev(&new_head) > 1 { *tail + (new_head - *tail).signum() } else { *tail }
 }
-